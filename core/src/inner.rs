@@ -1,4 +1,4 @@
-use crate::{Command, ListNode, Tab};
+use crate::{Command, ListNode, Requirement, Tab};
 use ego_tree::{NodeMut, Tree};
 use include_dir::{include_dir, Dir};
 use serde::Deserialize;
@@ -16,7 +16,8 @@ const TAB_DATA: Dir = include_dir!("$CARGO_MANIFEST_DIR/tabs");
 
 // Allow the unused TempDir to be stored for later destructor call
 #[allow(dead_code)]
-pub struct TabList(pub Vec<Tab>, TempDir);
+#[derive(Default)]
+pub struct TabList(pub Vec<Tab>, Option<TempDir>);
 
 // Implement deref to allow Vec<Tab> methods to be called on TabList
 impl Deref for TabList {
@@ -41,42 +42,219 @@ impl IntoIterator for TabList {
 }
 
 pub fn get_tabs(validate: bool) -> TabList {
-    let (temp_dir, tab_files) = TabDirectories::get_tabs();
+    let tabs = try_get_tabs(validate).unwrap_or_else(|e| panic!("{}", e));
+    if tabs.is_empty() {
+        panic!("No tabs found");
+    }
+    tabs
+}
+
+/// Like `get_tabs`, but reports broken tab data as an error instead of panicking. An empty list is
+/// not an error here, callers decide how to present it.
+pub fn try_get_tabs(validate: bool) -> Result<TabList, String> {
+    let (temp_dir, tab_files) = TabDirectories::get_tabs()?;
+
+    let mut tabs = Vec::new();
+    for path in tab_files {
+        let directory = path.parent().unwrap().to_owned();
+        let data = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read tab data {}: {}", path.display(), e))?;
+        let TabEntry { name, mut data } = toml::from_str(&data)
+            .map_err(|e| format!("Failed to parse tab data {}: {}", path.display(), e))?;
+
+        if validate {
+            filter_entries(&mut data);
+        }
 
-    let tabs: Vec<_> = tab_files
-        .into_iter()
-        .map(|path| {
-            let directory = path.parent().unwrap().to_owned();
-            let data = std::fs::read_to_string(path).expect("Failed to read tab data");
-            let mut tab_data: TabEntry = toml::from_str(&data).expect("Failed to parse tab data");
+        let mut tree = Tree::new(Rc::new(ListNode {
+            name: "root".to_string(),
+            description: String::new(),
+            command: Command::None,
+            task_list: String::new(),
+            multi_select: false,
+            requirements: Vec::new(),
+            dependencies: Vec::new(),
+            modifies: Vec::new(),
+            verify: None,
+            danger: false,
+        }));
+        let mut root = tree.root_mut();
+        create_directory(data, &mut root, &directory, validate, true, &[])?;
+        tabs.push(Tab { name, tree });
+    }
 
-            if validate {
-                filter_entries(&mut tab_data.data);
-            }
-            (tab_data, directory)
+    Ok(TabList(tabs, Some(temp_dir)))
+}
+
+/// A tab of the executable files in a directory, e.g. the user's own scripts, which need no tab
+/// data. Each one is a command named after its file, with the `# Description:` comment at its top
+/// as the description. Returns None for the tab when there are none, along with the files that
+/// were skipped and why
+pub fn scripts_tab(
+    name: &str,
+    dir: &Path,
+    validate: bool,
+) -> Result<(Option<Tab>, Vec<String>), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        // Hidden files are usually editor backups and the like
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| !name.to_string_lossy().starts_with('.'))
         })
+        .filter(|path| !path.is_dir())
         .collect();
+    paths.sort();
+
+    let mut tree = Tree::new(Rc::new(ListNode {
+        name: "root".to_string(),
+        description: String::new(),
+        command: Command::None,
+        task_list: String::new(),
+        multi_select: false,
+        requirements: Vec::new(),
+        dependencies: Vec::new(),
+        modifies: Vec::new(),
+        verify: None,
+        danger: false,
+    }));
+    let mut skipped = Vec::new();
+    for path in paths {
+        if !is_executable(&path) {
+            skipped.push(format!("{} is not executable", path.display()));
+            continue;
+        }
+        let (executable, args) = match read_shebang(&path) {
+            Ok(Some((executable, mut args))) => {
+                if validate && !is_executable(Path::new(&executable)) {
+                    skipped.push(format!(
+                        "{}: the interpreter of its shebang is not installed",
+                        path.display()
+                    ));
+                    continue;
+                }
+                args.push(path.to_string_lossy().to_string());
+                (executable, args)
+            }
+            // Frontends run scripts from their own directory, so the path has to be absolute
+            Ok(None) if is_elf(&path) => {
+                let program = std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone());
+                (program.to_string_lossy().to_string(), Vec::new())
+            }
+            Ok(None) => {
+                skipped.push(format!(
+                    "{} has no #! line naming its interpreter",
+                    path.display()
+                ));
+                continue;
+            }
+            Err(e) => {
+                skipped.push(format!("{}: {}", path.display(), e));
+                continue;
+            }
+        };
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        tree.root_mut().append(Rc::new(ListNode {
+            name,
+            description: script_description(&path),
+            command: Command::LocalFile {
+                executable,
+                args,
+                file: path,
+            },
+            task_list: String::new(),
+            multi_select: true,
+            requirements: Vec::new(),
+            dependencies: Vec::new(),
+            modifies: Vec::new(),
+            verify: None,
+            danger: false,
+        }));
+    }
+
+    let tab = tree.root().has_children().then(|| Tab {
+        name: name.to_string(),
+        tree,
+    });
+    Ok((tab, skipped))
+}
 
-    let tabs: Vec<Tab> = tabs
-        .into_iter()
-        .map(|(TabEntry { name, data }, directory)| {
-            let mut tree = Tree::new(Rc::new(ListNode {
-                name: "root".to_string(),
-                description: String::new(),
-                command: Command::None,
-                task_list: String::new(),
-                multi_select: false,
-            }));
-            let mut root = tree.root_mut();
-            create_directory(data, &mut root, &directory, validate, true);
-            Tab { name, tree }
+// "# Description: ..." among the comment lines a script starts with
+fn script_description(path: &Path) -> String {
+    let Ok(file) = File::open(path) else {
+        return String::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .take_while(|line| line.starts_with('#') || line.trim().is_empty())
+        .find_map(|line| {
+            line.trim_start_matches('#')
+                .trim()
+                .strip_prefix("Description:")
+                .map(|description| description.trim().to_string())
         })
-        .collect();
+        .unwrap_or_default()
+}
 
-    if tabs.is_empty() {
-        panic!("No tabs found");
+/// Checks a file in the tab_data.toml format without loading it, e.g. a user's own commands.
+/// Returns the problems found, which is empty for a valid file. Only an unreadable file is an
+/// error.
+pub fn validate_tab_file(path: &Path) -> Result<Vec<String>, String> {
+    let data = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    // Missing fields and wrong types surface here, with the line they are on
+    let TabEntry { name, data } = match toml::from_str(&data) {
+        Ok(tab) => tab,
+        Err(e) => return Ok(vec![e.to_string()]),
+    };
+
+    let mut problems = Vec::new();
+    if name.trim().is_empty() {
+        problems.push("The tab has no name".to_string());
+    }
+    let directory = path.parent().unwrap_or(Path::new("."));
+    validate_entries(&data, &name, directory, &mut problems);
+    Ok(problems)
+}
+
+fn validate_entries(
+    entries: &[Entry],
+    parent: &str,
+    command_dir: &Path,
+    problems: &mut Vec<String>,
+) {
+    if entries.is_empty() {
+        problems.push(format!("{} has no entries", parent));
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        let path = format!("{} › {}", parent, entry.name);
+        if entry.name.trim().is_empty() {
+            problems.push(format!("Entry {} of {} has no name", i + 1, parent));
+        }
+        // Commands are looked up by name, so a duplicate would shadow the first one
+        if entries[..i].iter().any(|other| other.name == entry.name) {
+            problems.push(format!("{} exists more than once", path));
+        }
+        match &entry.entry_type {
+            EntryType::Entries(entries) => validate_entries(entries, &path, command_dir, problems),
+            EntryType::Command(command) if command.trim().is_empty() => {
+                problems.push(format!("{} has an empty command", path));
+            }
+            EntryType::Command(_) => {}
+            EntryType::Script(script) => {
+                let script = command_dir.join(script);
+                if let Err(e) = File::open(&script) {
+                    problems.push(format!("{}: can't read {}: {}", path, script.display(), e));
+                }
+            }
+        }
     }
-    TabList(tabs, temp_dir)
 }
 
 #[derive(Deserialize)]
@@ -104,6 +282,14 @@ struct Entry {
     task_list: String,
     #[serde(default = "default_true")]
     multi_select: bool,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(default)]
+    modifies: Vec<PathBuf>,
+    #[serde(default)]
+    verify: Option<String>,
+    #[serde(default)]
+    danger: bool,
 }
 
 fn default_true() -> bool {
@@ -120,30 +306,28 @@ enum EntryType {
 
 impl Entry {
     fn is_supported(&self) -> bool {
-        self.preconditions.as_deref().is_none_or(|preconditions| {
-            preconditions.iter().all(
-                |Precondition {
-                     matches,
-                     data,
-                     values,
-                 }| {
-                    match data {
-                        SystemDataType::Environment(var_name) => std::env::var(var_name)
-                            .is_ok_and(|var| values.contains(&var) == *matches),
-                        SystemDataType::ContainingFile(file) => std::fs::read_to_string(file)
-                            .is_ok_and(|data| {
-                                values
-                                    .iter()
-                                    .all(|matching| data.contains(matching) == *matches)
-                            }),
-                        SystemDataType::CommandExists => values
-                            .iter()
-                            .all(|command| which::which(command).is_ok() == *matches),
-                        SystemDataType::FileExists => values.iter().all(|p| Path::new(p).is_file()),
-                    }
-                },
-            )
-        })
+        self.preconditions
+            .as_deref()
+            .is_none_or(|preconditions| preconditions.iter().all(Precondition::is_met))
+    }
+
+    fn requirements(&self) -> impl Iterator<Item = Requirement> + '_ {
+        self.preconditions
+            .iter()
+            .flatten()
+            .map(|precondition| Requirement {
+                description: precondition.describe(),
+                met: precondition.is_met(),
+            })
+    }
+
+    // Disk modifications and full system installs count as dangerous even without the flag
+    fn is_dangerous(&self) -> bool {
+        self.danger
+            || self
+                .task_list
+                .split_whitespace()
+                .any(|task| matches!(task, "D" | "SI"))
     }
 }
 
@@ -165,6 +349,46 @@ enum SystemDataType {
     CommandExists,
 }
 
+impl Precondition {
+    fn is_met(&self) -> bool {
+        let Precondition {
+            matches,
+            data,
+            values,
+        } = self;
+        match data {
+            SystemDataType::Environment(var_name) => {
+                std::env::var(var_name).is_ok_and(|var| values.contains(&var) == *matches)
+            }
+            SystemDataType::ContainingFile(file) => {
+                std::fs::read_to_string(file).is_ok_and(|data| {
+                    values
+                        .iter()
+                        .all(|matching| data.contains(matching) == *matches)
+                })
+            }
+            SystemDataType::CommandExists => values
+                .iter()
+                .all(|command| which::which(command).is_ok() == *matches),
+            SystemDataType::FileExists => values.iter().all(|p| Path::new(p).is_file()),
+        }
+    }
+
+    // Short, human readable form, e.g. "pacman" or "ID=debian"
+    fn describe(&self) -> String {
+        let values = self.values.join(" | ");
+        match (&self.data, self.matches) {
+            (SystemDataType::Environment(var_name), true) => format!("{}={}", var_name, values),
+            (SystemDataType::Environment(var_name), false) => format!("{}≠{}", var_name, values),
+            (SystemDataType::ContainingFile(_), true) => values,
+            (SystemDataType::ContainingFile(_), false) => format!("not {}", values),
+            (SystemDataType::CommandExists, true) => values,
+            (SystemDataType::CommandExists, false) => format!("no {}", values),
+            (SystemDataType::FileExists, _) => format!("{} exists", values),
+        }
+    }
+}
+
 fn filter_entries(entries: &mut Vec<Entry>) {
     entries.retain_mut(|entry| {
         if !entry.is_supported() {
@@ -185,10 +409,17 @@ fn create_directory(
     command_dir: &Path,
     validate: bool,
     parent_multi_select: bool,
-) {
+    parent_requirements: &[Requirement],
+) -> Result<(), String> {
     for entry in data {
         let multi_select = parent_multi_select && entry.multi_select;
+        let requirements: Vec<Requirement> = parent_requirements
+            .iter()
+            .cloned()
+            .chain(entry.requirements())
+            .collect();
 
+        let danger = entry.is_dangerous();
         match entry.entry_type {
             EntryType::Entries(entries) => {
                 let mut node = node.append(Rc::new(ListNode {
@@ -197,8 +428,20 @@ fn create_directory(
                     command: Command::None,
                     task_list: String::new(),
                     multi_select,
+                    requirements: requirements.clone(),
+                    dependencies: Vec::new(),
+                    modifies: Vec::new(),
+                    verify: None,
+                    danger: false,
                 }));
-                create_directory(entries, &mut node, command_dir, validate, multi_select);
+                create_directory(
+                    entries,
+                    &mut node,
+                    command_dir,
+                    validate,
+                    multi_select,
+                    &requirements,
+                )?;
             }
             EntryType::Command(command) => {
                 node.append(Rc::new(ListNode {
@@ -207,12 +450,17 @@ fn create_directory(
                     command: Command::Raw(command),
                     task_list: String::new(),
                     multi_select,
+                    requirements,
+                    dependencies: entry.dependencies,
+                    modifies: entry.modifies,
+                    verify: entry.verify,
+                    danger,
                 }));
             }
             EntryType::Script(script) => {
                 let script = command_dir.join(script);
                 if !script.exists() {
-                    panic!("Script {} does not exist", script.display());
+                    return Err(format!("Script {} does not exist", script.display()));
                 }
 
                 if let Some((executable, args)) = get_shebang(&script, validate) {
@@ -226,40 +474,69 @@ fn create_directory(
                         },
                         task_list: entry.task_list,
                         multi_select,
+                        requirements,
+                        dependencies: entry.dependencies,
+                        modifies: entry.modifies,
+                        verify: entry.verify,
+                        danger,
                     }));
                 }
             }
         }
     }
+    Ok(())
 }
 
+// Interpreter and arguments of a tab data script, the script itself last. None when it can't run:
+// its shebang is unusable, or names an interpreter that isn't installed when validating
 fn get_shebang(script_path: &Path, validate: bool) -> Option<(String, Vec<String>)> {
-    let default_executable = || Some(("/bin/sh".into(), vec!["-e".into()]));
+    let (executable, mut args) = match read_shebang(script_path) {
+        Ok(Some(shebang)) => shebang,
+        Ok(None) => ("/bin/sh".into(), vec!["-e".into()]),
+        Err(_) => return None,
+    };
+
+    let is_valid = !validate || is_executable(Path::new(&executable));
 
-    let script = File::open(script_path).expect("Failed to open script file");
+    is_valid.then(|| {
+        args.push(script_path.to_string_lossy().to_string());
+        (executable, args)
+    })
+}
+
+// The program and arguments of a script's "#!" line. None when it doesn't start with one, an
+// error when the file can't be read or the line names no program
+fn read_shebang(script_path: &Path) -> Result<Option<(String, Vec<String>)>, String> {
+    let script = File::open(script_path).map_err(|e| format!("can't be read: {}", e))?;
     let mut reader = BufReader::new(script);
 
     // Take the first 2 characters from the reader; check whether it's a shebang
     let mut two_chars = [0; 2];
     if reader.read_exact(&mut two_chars).is_err() || two_chars != *b"#!" {
-        return default_executable();
+        return Ok(None);
     }
 
-    let first_line = reader.lines().next().unwrap().unwrap();
+    let mut first_line = String::new();
+    reader
+        .read_line(&mut first_line)
+        .map_err(|_| "its #! line is not valid UTF-8".to_string())?;
 
     let mut parts = first_line.split_whitespace();
+    let executable = parts
+        .next()
+        .ok_or_else(|| "its #! line names no interpreter".to_string())?;
+    Ok(Some((
+        executable.to_string(),
+        parts.map(ToString::to_string).collect(),
+    )))
+}
 
-    let Some(executable) = parts.next() else {
-        return default_executable();
-    };
-
-    let is_valid = !validate || is_executable(Path::new(executable));
-
-    is_valid.then(|| {
-        let mut args: Vec<String> = parts.map(ToString::to_string).collect();
-        args.push(script_path.to_string_lossy().to_string());
-        (executable.to_string(), args)
-    })
+// Compiled programs are run directly. Anything else without a "#!" line is a script whose
+// interpreter can only be guessed
+fn is_elf(path: &Path) -> bool {
+    let mut magic = [0; 4];
+    File::open(path).is_ok_and(|mut file| file.read_exact(&mut magic).is_ok())
+        && magic == *b"\x7fELF"
 }
 
 fn is_executable(path: &Path) -> bool {
@@ -269,20 +546,22 @@ fn is_executable(path: &Path) -> bool {
 }
 
 impl TabDirectories {
-    fn get_tabs() -> (TempDir, Vec<PathBuf>) {
-        let temp_dir = TempDir::with_prefix("linutil_scripts").unwrap();
+    fn get_tabs() -> Result<(TempDir, Vec<PathBuf>), String> {
+        let temp_dir = TempDir::with_prefix("linutil_scripts")
+            .map_err(|e| format!("Failed to create a temporary directory: {}", e))?;
         TAB_DATA
             .extract(&temp_dir)
-            .expect("Failed to extract the saved directory");
+            .map_err(|e| format!("Failed to extract the saved directory: {}", e))?;
 
         let tab_files = std::fs::read_to_string(temp_dir.path().join("tabs.toml"))
-            .expect("Failed to read tabs.toml");
-        let data: Self = toml::from_str(&tab_files).expect("Failed to parse tabs.toml");
+            .map_err(|e| format!("Failed to read tabs.toml: {}", e))?;
+        let data: Self = toml::from_str(&tab_files)
+            .map_err(|e| format!("Failed to parse tabs.toml: {}", e))?;
         let tab_paths = data
             .directories
             .iter()
             .map(|path| temp_dir.path().join(path).join("tab_data.toml"))
             .collect();
-        (temp_dir, tab_paths)
+        Ok((temp_dir, tab_paths))
     }
 }
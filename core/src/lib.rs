@@ -8,7 +8,7 @@ use ego_tree::Tree;
 use std::path::PathBuf;
 
 pub use config::{Config, ConfigValues};
-pub use inner::{get_tabs, TabList};
+pub use inner::{get_tabs, scripts_tab, try_get_tabs, validate_tab_file, TabList};
 
 #[derive(Clone, Hash, Eq, PartialEq)]
 pub enum Command {
@@ -35,13 +35,53 @@ pub struct ListNode {
     pub command: Command,
     pub task_list: String,
     pub multi_select: bool,
+    /// Preconditions of the entry and of all its parent directories
+    pub requirements: Vec<Requirement>,
+    /// Binaries the command expects on PATH. Unlike preconditions these are checked right before
+    /// the command runs, since they may be installed in the meantime
+    pub dependencies: Vec<String>,
+    /// Files the command changes, e.g. /etc/ssh/sshd_config, so frontends can show what it did
+    /// to them
+    pub modifies: Vec<PathBuf>,
+    /// Shell command that checks whether the command did its job, e.g. `command -v docker` after
+    /// installing Docker. Frontends run it after the command succeeded
+    pub verify: Option<String>,
+    /// Irreversible, e.g. partitions disks or deletes accounts. Frontends ask for more than a
+    /// single click before running these
+    pub danger: bool,
+}
+
+#[derive(Clone, Hash, Eq, PartialEq)]
+pub struct Requirement {
+    pub description: String,
+    /// Whether the current system satisfies this requirement
+    pub met: bool,
+}
+
+impl ListNode {
+    /// Directories have no command of their own, only entries; this holds for empty ones too
+    pub fn is_directory(&self) -> bool {
+        matches!(self.command, Command::None)
+    }
+
+    pub fn is_compatible(&self) -> bool {
+        self.requirements.iter().all(|requirement| requirement.met)
+    }
+
+    pub fn missing_dependencies(&self) -> Vec<&str> {
+        self.dependencies
+            .iter()
+            .filter(|dependency| which::which(dependency).is_err())
+            .map(String::as_str)
+            .collect()
+    }
 }
 
 impl Tab {
     fn find_command_by_name(&self, name: &str) -> Option<Rc<ListNode>> {
         self.tree.root().descendants().find_map(|node| {
             let node_value = node.value();
-            (node_value.name == name && !node.has_children()).then_some(node_value.clone())
+            (node_value.name == name && !node_value.is_directory()).then_some(node_value.clone())
         })
     }
 }
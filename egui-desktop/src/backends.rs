@@ -0,0 +1,189 @@
+use linutil_core::{Command, ListNode};
+use std::{env, fs, path::PathBuf};
+
+// Package managers that commands are written for, and which of them this system has. The tab data
+// has no field for it, so the package manager of a command is guessed from its body.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum PackageManager {
+    Apt,
+    Dnf,
+    Pacman,
+    Zypper,
+    Flatpak,
+    Snap,
+}
+
+impl PackageManager {
+    pub const ALL: [PackageManager; 6] = [
+        PackageManager::Apt,
+        PackageManager::Dnf,
+        PackageManager::Pacman,
+        PackageManager::Zypper,
+        PackageManager::Flatpak,
+        PackageManager::Snap,
+    ];
+
+    pub fn binary(self) -> &'static str {
+        match self {
+            PackageManager::Apt => "apt",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Zypper => "zypper",
+            PackageManager::Flatpak => "flatpak",
+            PackageManager::Snap => "snap",
+        }
+    }
+}
+
+/// The package managers found on PATH
+pub fn probe() -> Vec<PackageManager> {
+    PackageManager::ALL
+        .into_iter()
+        .filter(|manager| find_on_path(manager.binary()).is_some())
+        .collect()
+}
+
+/// Where PATH resolves a program name to, like `command -v`
+pub fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let paths = env::var_os("PATH")?;
+    env::split_paths(&paths)
+        .map(|dir| dir.join(binary))
+        .find(|path| path.is_file())
+}
+
+/// The package manager a command can't work without. Only a body mentioning exactly one of them
+/// counts: scripts supporting several distros mention each one they handle.
+pub fn required_by(node: &ListNode) -> Option<PackageManager> {
+    let body = command_body(node)?;
+    let mut mentioned = PackageManager::ALL.into_iter().filter(|manager| {
+        // Splitting on anything but letters makes "apt-get" count as apt
+        body.split(|c: char| !c.is_ascii_alphabetic())
+            .any(|word| word == manager.binary())
+    });
+    match (mentioned.next(), mentioned.next()) {
+        (Some(manager), None) => Some(manager),
+        _ => None,
+    }
+}
+
+/// The inline command, or the content of the script
+pub fn command_body(node: &ListNode) -> Option<String> {
+    match &node.command {
+        Command::Raw(cmd) => Some(cmd.clone()),
+        Command::LocalFile { file, .. } => fs::read_to_string(file).ok(),
+        Command::None => None,
+    }
+}
+
+/// A package operation written out literally in a command, e.g. `apt-get install -y curl git`
+pub struct PackageOp {
+    pub manager: PackageManager,
+    pub remove: bool,
+    pub packages: Vec<String>,
+}
+
+impl PackageOp {
+    /// The command line that shows what the operation would change without changing anything, from
+    /// the local package database so it doesn't wait for mirrors. None when the package manager has
+    /// no such mode
+    pub fn dry_run(&self) -> Option<Vec<String>> {
+        let (install, remove): (&[&str], &[&str]) = match self.manager {
+            PackageManager::Apt => (
+                &["apt-get", "--simulate", "install"],
+                &["apt-get", "--simulate", "remove"],
+            ),
+            PackageManager::Dnf => (
+                &["dnf", "--cacheonly", "--assumeno", "install"],
+                &["dnf", "--cacheonly", "--assumeno", "remove"],
+            ),
+            PackageManager::Pacman => (
+                &["pacman", "-S", "--print", "--print-format", "%n %v"],
+                &["pacman", "-R", "--print", "--print-format", "%n %v"],
+            ),
+            PackageManager::Zypper => (
+                &[
+                    "zypper",
+                    "--non-interactive",
+                    "--no-refresh",
+                    "install",
+                    "--dry-run",
+                ],
+                &[
+                    "zypper",
+                    "--non-interactive",
+                    "--no-refresh",
+                    "remove",
+                    "--dry-run",
+                ],
+            ),
+            PackageManager::Flatpak | PackageManager::Snap => return None,
+        };
+        let command = if self.remove { remove } else { install };
+        Some(
+            command
+                .iter()
+                .map(|arg| arg.to_string())
+                .chain(self.packages.iter().cloned())
+                .collect(),
+        )
+    }
+}
+
+/// The package operations of a command body whose packages are spelled out. Operations on
+/// variables, like `"$PACKAGER" install "$pkg"`, can't be previewed and are left out
+pub fn package_ops(body: &str) -> Vec<PackageOp> {
+    body.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.split([';', '|', '&']))
+        .filter_map(parse_package_op)
+        .collect()
+}
+
+fn parse_package_op(segment: &str) -> Option<PackageOp> {
+    let mut words = segment.split_whitespace();
+    let manager = words.find_map(|word| match word.trim_matches(['"', '\'']) {
+        "apt" | "apt-get" => Some(PackageManager::Apt),
+        "dnf" => Some(PackageManager::Dnf),
+        "pacman" => Some(PackageManager::Pacman),
+        "zypper" => Some(PackageManager::Zypper),
+        _ => None,
+    })?;
+    let remove = match manager {
+        // The operation comes first: -S, possibly with y and u, or -R
+        PackageManager::Pacman => {
+            let operation = words.next()?;
+            if operation.starts_with("-R") {
+                true
+            } else if operation.starts_with("-S")
+                && operation[2..].chars().all(|c| "yu".contains(c))
+            {
+                false
+            } else {
+                return None;
+            }
+        }
+        _ => match words.find(|word| !word.starts_with('-'))? {
+            "install" | "in" => false,
+            "remove" | "purge" | "erase" | "rm" => true,
+            _ => return None,
+        },
+    };
+
+    let mut packages = Vec::new();
+    for word in words {
+        if word.starts_with('-') {
+            continue;
+        }
+        // Variables, globs and redirections: what gets installed is only known when it runs
+        if word.contains(['$', '`', '*', '(', '<', '>', '"', '\'']) {
+            return None;
+        }
+        packages.push(word.to_string());
+    }
+    (!packages.is_empty()).then_some(PackageOp {
+        manager,
+        remove,
+        packages,
+    })
+}
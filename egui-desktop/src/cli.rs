@@ -0,0 +1,393 @@
+use crate::{
+    command_line,
+    config::AppConfig,
+    exec::{run_node, ExecOptions},
+    exec_log, execute_with_retries, history,
+    history::HistoryEntry,
+    load_all_tabs, selftest, truncation_notice, verify_result,
+};
+use linutil_core::{validate_tab_file, Command, ListNode, TabList};
+use serde::Serialize;
+use std::{
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+pub const USAGE: &str = "\
+Usage: linutil-egui [OPTIONS]
+
+Without options the desktop app starts.
+
+Options:
+  --list                         Print the path and description of every command and exit
+  --list --json                  The same as JSON, grouped by tab
+  --execute <tab>/<command>      Run a command without the GUI and exit with its exit code
+  --execute ... --dry-run        Print what the command would run instead of running it
+  --execute ... --timeout <secs> Kill the command after this many seconds, 0 for no limit
+  --validate <file>              Check a file in the tab_data.toml format and list its problems
+  --self-test                    Check that commands can run on this system; exits 1 if not
+  --self-test --json             The same as JSON
+  --safe                         Start the desktop app with execution disabled
+  -h, --help                     Print this help";
+
+pub enum Mode {
+    Gui {
+        /// Started with --safe, which disables execution for the session
+        safe: bool,
+    },
+    Help,
+    List {
+        json: bool,
+    },
+    Execute(Execute),
+    Validate(PathBuf),
+    SelfTest {
+        json: bool,
+    },
+}
+
+pub struct Execute {
+    /// Slash separated path of the command, as printed by --list
+    path: String,
+    dry_run: bool,
+    /// Seconds, overriding AppConfig::command_timeout_secs
+    timeout: Option<u64>,
+}
+
+pub fn parse(args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let (safe, args): (Vec<String>, Vec<String>) = args.partition(|arg| arg == "--safe");
+    let mut args = args.into_iter();
+    let Some(arg) = args.next() else {
+        return Ok(Mode::Gui {
+            safe: !safe.is_empty(),
+        });
+    };
+    if !safe.is_empty() && arg.starts_with("--execute") {
+        return Err("--execute can't be combined with --safe".to_string());
+    }
+
+    let mode = match arg.as_str() {
+        "-h" | "--help" => Mode::Help,
+        "--list" => match args.next().as_deref() {
+            None => Mode::List { json: false },
+            Some("--json") => Mode::List { json: true },
+            Some(extra) => return Err(format!("Unexpected argument: {}", extra)),
+        },
+        "--self-test" => match args.next().as_deref() {
+            None => Mode::SelfTest { json: false },
+            Some("--json") => Mode::SelfTest { json: true },
+            Some(extra) => return Err(format!("Unexpected argument: {}", extra)),
+        },
+        "--validate" => Mode::Validate(
+            args.next()
+                .map(PathBuf::from)
+                .ok_or_else(|| "--validate needs the path of a file".to_string())?,
+        ),
+        "--execute" => {
+            let path = args
+                .next()
+                .ok_or_else(|| "--execute needs the path of a command".to_string())?;
+            return execute_options(path, args);
+        }
+        _ => match arg.strip_prefix("--execute=") {
+            Some(path) => return execute_options(path.to_string(), args),
+            None => return Err(format!("Unknown argument: {}", arg)),
+        },
+    };
+
+    match args.next() {
+        Some(extra) => Err(format!("Unexpected argument: {}", extra)),
+        None => Ok(mode),
+    }
+}
+
+// The options --execute takes after the path of the command
+fn execute_options(path: String, mut args: impl Iterator<Item = String>) -> Result<Mode, String> {
+    let mut execute = Execute {
+        path,
+        dry_run: false,
+        timeout: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--dry-run" => execute.dry_run = true,
+            "--timeout" => {
+                let secs = args
+                    .next()
+                    .ok_or_else(|| "--timeout needs a number of seconds".to_string())?;
+                execute.timeout = Some(
+                    secs.parse()
+                        .map_err(|_| format!("Not a number of seconds: {}", secs))?,
+                );
+            }
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+    Ok(Mode::Execute(execute))
+}
+
+/// Runs a headless mode and returns the exit code of the process
+pub fn run(mode: Mode) -> i32 {
+    let config = AppConfig::load();
+    let tabs = match mode {
+        Mode::Gui { .. } => return 0,
+        Mode::Help => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        Mode::Validate(path) => return validate(&path),
+        Mode::SelfTest { json } => return self_test(&config, json),
+        Mode::List { .. } | Mode::Execute(_) => match load_all_tabs(&config) {
+            Ok((tabs, script_problems)) => {
+                for problem in script_problems {
+                    eprintln!("My Scripts: {}", problem);
+                }
+                tabs
+            }
+            Err(e) => {
+                eprintln!("Failed to load the tab data: {}", e);
+                return 1;
+            }
+        },
+    };
+
+    let commands = commands(&tabs);
+    match mode {
+        Mode::Execute(options) => execute(&config, &commands, &options),
+        Mode::List { json: true } => list_json(&tabs, &commands),
+        _ => {
+            for (path, node) in &commands {
+                if node.description.is_empty() {
+                    println!("{}", path.join("/"));
+                } else {
+                    println!("{}\t{}", path.join("/"), node.description);
+                }
+            }
+            0
+        }
+    }
+}
+
+fn validate(path: &Path) -> i32 {
+    match validate_tab_file(path) {
+        Ok(problems) if problems.is_empty() => {
+            println!("{} is valid", path.display());
+            0
+        }
+        Ok(problems) => {
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            eprintln!("{} problem(s) in {}", problems.len(), path.display());
+            1
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            2
+        }
+    }
+}
+
+fn self_test(config: &AppConfig, json: bool) -> i32 {
+    let checks = selftest::run(config);
+    if json {
+        match serde_json::to_string_pretty(&checks) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize the report: {}", e);
+                return 1;
+            }
+        }
+    } else {
+        for check in &checks {
+            println!("{} {}: {}", check.icon(), check.name, check.detail);
+        }
+    }
+    i32::from(selftest::failed(&checks))
+}
+
+// Mirrors TabInfo / EntryInfo from DATA_FLOW_DOCUMENTATION.md, so tooling sees the same shape
+#[derive(Serialize)]
+struct TabInfo<'a> {
+    name: &'a str,
+    entries: Vec<EntryInfo<'a>>,
+}
+
+#[derive(Serialize)]
+struct EntryInfo<'a> {
+    name: &'a str,
+    description: &'a str,
+    /// What --execute takes to run the command
+    path: String,
+}
+
+fn list_json(tabs: &TabList, commands: &[(Vec<String>, Rc<ListNode>)]) -> i32 {
+    let tabs: Vec<TabInfo> = tabs
+        .iter()
+        .map(|tab| TabInfo {
+            name: &tab.name,
+            entries: commands
+                .iter()
+                .filter(|(path, _)| path[0] == tab.name)
+                .map(|(path, node)| EntryInfo {
+                    name: &node.name,
+                    description: &node.description,
+                    path: path.join("/"),
+                })
+                .collect(),
+        })
+        .collect();
+
+    match serde_json::to_string_pretty(&tabs) {
+        Ok(json) => {
+            println!("{}", json);
+            0
+        }
+        Err(e) => {
+            eprintln!("Failed to serialize the command list: {}", e);
+            1
+        }
+    }
+}
+
+fn execute(config: &AppConfig, commands: &[(Vec<String>, Rc<ListNode>)], options: &Execute) -> i32 {
+    if config.safe_mode {
+        eprintln!("Execution is disabled in safe mode, turn it off in the settings first");
+        return 1;
+    }
+    let Some((path, node)) = find_command(commands, &options.path) else {
+        eprintln!(
+            "No command {}, see --list for the available ones",
+            options.path
+        );
+        return 1;
+    };
+    // Same key as the GUI uses for the history and the per-command settings
+    let key = path.join(" › ");
+
+    let missing = node.missing_dependencies();
+    if !missing.is_empty() {
+        eprintln!("Warning: not found on PATH: {}", missing.join(", "));
+    }
+
+    let defaults = config.exec_options();
+    let opts = ExecOptions {
+        env: config.env_for(&key),
+        cwd: config.command_dir(),
+        dry_run: options.dry_run,
+        timeout: match options.timeout {
+            Some(secs) => (secs > 0).then(|| Duration::from_secs(secs)),
+            None => defaults.timeout,
+        },
+        ..defaults
+    };
+    if opts.dry_run {
+        // Nothing runs, so there is nothing to log or to record in the history either
+        println!("{}", run_node(node, &opts).output);
+        return 0;
+    }
+    let mut result = execute_with_retries(node, &opts, config.retry_policy(), |attempt, delay| {
+        eprintln!(
+            "Failed, starting attempt {} in {}s",
+            attempt,
+            delay.as_secs()
+        )
+    });
+
+    // Output is captured, so it only comes once the last attempt is done
+    for attempt in result.failed_attempts.iter().chain([&result]) {
+        if let Some(notice) = truncation_notice(attempt) {
+            eprintln!("{}", notice);
+        }
+        for line in &attempt.lines {
+            if line.is_stderr {
+                eprintln!("{}", line.text);
+            } else {
+                println!("{}", line.text);
+            }
+        }
+        // Nothing was captured, e.g. the command could not be spawned at all
+        if let Some(error) = attempt.error.as_ref().filter(|_| attempt.lines.is_empty()) {
+            eprintln!("{}", error);
+        }
+    }
+
+    verify_result(node, &opts, &mut result);
+    if let Some(verification) = &result.verification {
+        let outcome = if verification.passed {
+            "Verified"
+        } else {
+            "Verification failed"
+        };
+        eprintln!("{}: {}", outcome, verification.command);
+        if !verification.output.is_empty() {
+            eprintln!("{}", verification.output);
+        }
+    }
+
+    if let Some(log) = config.exec_log_path() {
+        let entry = exec_log::LogEntry::new(&key, command_line(&node.command), &result);
+        if let Err(e) = exec_log::append(&log, &entry) {
+            eprintln!("Failed to write the command log {}: {}", log.display(), e);
+        }
+    }
+
+    let entry = HistoryEntry::new(key, node.name.clone(), result.success, result.elapsed);
+    if let Err(e) = history::record(&entry) {
+        eprintln!("Failed to record command history: {}", e);
+    }
+
+    match result.exit_code {
+        // The command exited with 0, but its verification failed
+        Some(0) if !result.success => 1,
+        Some(code) => code,
+        None if result.success => 0,
+        None => 1,
+    }
+}
+
+// A path matches when it is the full path of a command, or just its tab and name as long as that
+// is unique within the tab
+fn find_command<'a>(
+    commands: &'a [(Vec<String>, Rc<ListNode>)],
+    path: &str,
+) -> Option<(&'a [String], &'a ListNode)> {
+    let wanted: Vec<&str> = path.split('/').map(str::trim).collect();
+    if let Some((path, node)) = commands.iter().find(|(path, _)| *path == wanted) {
+        return Some((path, node));
+    }
+
+    let [tab, name] = wanted.as_slice() else {
+        return None;
+    };
+    let mut matches = commands.iter().filter(|(path, _)| {
+        path.first().is_some_and(|t| t == tab) && path.last().is_some_and(|n| n == name)
+    });
+    match (matches.next(), matches.next()) {
+        (Some((path, node)), None) => Some((path, node)),
+        _ => None,
+    }
+}
+
+// Every command of every tab with the names leading to it, starting with the tab
+fn commands(tabs: &TabList) -> Vec<(Vec<String>, Rc<ListNode>)> {
+    let mut commands = Vec::new();
+    for tab in tabs.iter() {
+        for node in tab.tree.root().descendants() {
+            if node.has_children() || matches!(node.value().command, Command::None) {
+                continue;
+            }
+            let mut path: Vec<String> = node
+                .ancestors()
+                .filter(|ancestor| ancestor.parent().is_some())
+                .map(|ancestor| ancestor.value().name.clone())
+                .collect();
+            path.reverse();
+            path.insert(0, tab.name.clone());
+            path.push(node.value().name.clone());
+            commands.push((path, node.value().clone()));
+        }
+    }
+    commands
+}
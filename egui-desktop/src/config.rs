@@ -0,0 +1,496 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::PathBuf,
+};
+
+// Settings of the desktop app, persisted as JSON in the user's config directory.
+// Every field has a default so older config files keep loading as new options are added.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Prefix each captured output line with the time elapsed since the command started
+    pub timestamp_output: bool,
+    /// How output lines that are not valid UTF-8 are decoded
+    pub output_encoding: OutputEncoding,
+    /// Output kept of each command, in MiB. Beyond it the oldest lines are dropped, so noisy
+    /// commands can't exhaust memory or freeze the output window
+    pub max_output_mb: u64,
+    /// Save the complete output of a command to a file once it exceeds max_output_mb
+    pub save_full_output: bool,
+    /// Leave the output window closed when every command succeeded, and say so in the status bar
+    /// for toast_secs instead. Failures still open it
+    pub auto_close_output: bool,
+    /// Show notices in the corner of the window when runs start and finish or something is copied
+    pub show_toasts: bool,
+    /// Show the results of the session in a panel below the commands instead of opening the
+    /// output window after each run
+    pub output_dock: bool,
+    /// Results the output dock keeps; older ones are dropped
+    pub output_dock_max: usize,
+    pub toast_secs: u64,
+    /// Which of stdout and stderr the output window shows, and how
+    pub output_streams: OutputStreams,
+    /// Sidebar order of the tabs, by name. Tabs missing from the list keep their original order
+    /// after the listed ones
+    pub tab_order: Vec<String>,
+    /// Names of the tabs that are not shown in the sidebar
+    pub hidden_tabs: BTreeSet<String>,
+    /// How many commands of an "Execute All" batch may run at once. 1 keeps them serial, which is
+    /// what most scripts with ordering dependencies need
+    pub max_parallel: usize,
+    /// Skip the rest of a batch once one of its commands fails, instead of running everything
+    pub stop_on_failure: bool,
+    /// How often a failed command that downloads or installs something is run again. 0 is off
+    pub retry_attempts: u32,
+    /// Seconds before the first retry; each further retry waits twice as long
+    pub retry_delay_secs: u64,
+    /// Check the preconditions of the tab data and hide commands that can't work on this system
+    pub validate: bool,
+    /// Run commands straight away instead of showing the confirmation dialog first
+    pub skip_confirmation: bool,
+    /// Show the free space of / before running install commands, and insist on confirmation when
+    /// it is below min_free_space_mb
+    pub disk_space_check: bool,
+    pub min_free_space_mb: u64,
+    /// Browse and preview only: every way of running a command is disabled, e.g. on demo machines
+    pub safe_mode: bool,
+    /// Ask before closing the window while commands are running or queued, as closing stops them
+    pub confirm_close: bool,
+    /// Minutes without input after which the app returns to the first tab and drops the
+    /// selection, search and open windows, for shared terminals. 0 is off
+    pub idle_reset_mins: u64,
+    /// Paths of commands that run without confirmation even when skip_confirmation is off
+    pub skip_confirm_for: BTreeSet<String>,
+    /// Extra environment variables for every command, e.g. proxy settings or the locale
+    pub env: BTreeMap<String, String>,
+    /// Named sets of variables, e.g. "Work" with proxy settings, of which one can be active
+    pub env_profiles: BTreeMap<String, BTreeMap<String, String>>,
+    /// Name of the active profile in env_profiles. Empty when none is
+    pub env_profile: String,
+    /// Per-command environment variables, keyed by command path ("Tab › Directory › Command")
+    pub command_env: BTreeMap<String, BTreeMap<String, String>>,
+    /// Environment variables whose name contains one of these, ignoring case, have their values
+    /// left out of the diagnostic report
+    pub redact_patterns: Vec<String>,
+    /// Working directory of inline commands. Empty runs them from the directory the app was
+    /// started in; scripts always run from their own directory
+    pub command_dir: String,
+    /// Shell running inline commands, e.g. bash for bashisms. Empty uses sh
+    pub shell: String,
+    /// Seconds an inline command or script may run before it is killed. 0 lets it run for as
+    /// long as it takes
+    pub command_timeout_secs: u64,
+    /// File every executed command is logged to. Empty uses commands.log in the data directory
+    pub exec_log: String,
+    /// Directory of the user's own scripts, shown as the "My Scripts" tab. Empty uses scripts in
+    /// the data directory
+    pub scripts_dir: String,
+    /// Language of the UI, e.g. "de". Empty follows the locale of the environment ($LANG)
+    pub language: String,
+    pub theme: ThemeVariant,
+    /// RGB color replacing the primary and accent color of the theme
+    pub accent_color: Option<[u8; 3]>,
+    /// Light surfaces and dark text instead of the dark default, for any theme variant
+    pub light_mode: bool,
+    /// Icons of the command cards. Uses the names of the TUI's --theme values, so both frontends
+    /// agree on what "compatible" means
+    pub icon_theme: IconTheme,
+    /// Render the Markdown of command descriptions (bold, code spans, bullet lists) instead of
+    /// showing the raw text
+    pub markdown_descriptions: bool,
+    /// Lay the app out in windows below the minimum size instead of showing a "too small" notice,
+    /// like the TUI's --size-bypass
+    pub size_bypass: bool,
+    pub sidebar: SidebarMode,
+    /// How the entries of a folder are laid out
+    pub list_density: ListDensity,
+    /// Key bindings the overrides in keybindings start from
+    pub keymap_preset: KeymapPreset,
+    /// Keys per action, replacing the preset's, e.g. "select_next": ["J", "ArrowDown"]. Keys are
+    /// egui key names with optional "Ctrl+", "Alt+" and "Shift+" prefixes; an empty list unbinds
+    /// the action. See shortcuts::Action::config_name for the action names
+    pub keybindings: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeVariant {
+    #[default]
+    Modern,
+    HighContrast,
+    /// Okabe-Ito based palette that stays distinguishable with the common forms of color blindness
+    ColorBlind,
+}
+
+// Mirrors the TUI's theme::Theme, which the desktop app can't depend on
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum IconTheme {
+    /// Emoji icons
+    #[default]
+    Default,
+    /// Plain ASCII, for fonts without emoji
+    Compatible,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KeymapPreset {
+    #[default]
+    Default,
+    /// Adds h/j/k/l for back, down, up and open to the default keys
+    Vim,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SidebarMode {
+    /// Tab names in a 280px wide panel
+    #[default]
+    Full,
+    /// A narrow strip of tab abbreviations, with the names as tooltips
+    Rail,
+    Hidden,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ListDensity {
+    /// A grid of cards with the full description, notes and requirements
+    #[default]
+    Cards,
+    /// One line per entry, with the details as tooltips, to see more of a long folder at once
+    Compact,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputEncoding {
+    /// Replace invalid bytes with U+FFFD
+    #[default]
+    Utf8,
+    /// Decode the line as Latin-1, which maps every byte to a character
+    Latin1,
+    /// Show the raw bytes of the line as hex
+    Hex,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStreams {
+    /// Both streams in the order the lines arrived
+    Interleaved,
+    StdoutOnly,
+    StderrOnly,
+    /// stdout, then stderr, each under its own heading
+    #[default]
+    BothLabeled,
+}
+
+impl OutputStreams {
+    pub const ALL: [OutputStreams; 4] = [
+        OutputStreams::Interleaved,
+        OutputStreams::StdoutOnly,
+        OutputStreams::StderrOnly,
+        OutputStreams::BothLabeled,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputStreams::Interleaved => "Interleaved",
+            OutputStreams::StdoutOnly => "stdout only",
+            OutputStreams::StderrOnly => "stderr only",
+            OutputStreams::BothLabeled => "stdout and stderr, labeled",
+        }
+    }
+}
+
+impl OutputEncoding {
+    pub const ALL: [OutputEncoding; 3] = [
+        OutputEncoding::Utf8,
+        OutputEncoding::Latin1,
+        OutputEncoding::Hex,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            OutputEncoding::Utf8 => "Replace invalid bytes",
+            OutputEncoding::Latin1 => "Decode as Latin-1",
+            OutputEncoding::Hex => "Show as hex",
+        }
+    }
+
+    /// Decodes a line that failed to parse as UTF-8
+    pub fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            OutputEncoding::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            OutputEncoding::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+            OutputEncoding::Hex => bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+impl ThemeVariant {
+    pub const ALL: [ThemeVariant; 3] = [
+        ThemeVariant::Modern,
+        ThemeVariant::HighContrast,
+        ThemeVariant::ColorBlind,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ThemeVariant::Modern => "Modern",
+            ThemeVariant::HighContrast => "High contrast",
+            ThemeVariant::ColorBlind => "Color-blind friendly",
+        }
+    }
+}
+
+impl SidebarMode {
+    /// The mode the sidebar toggle switches to: full → rail → hidden → full
+    pub fn next(self) -> Self {
+        match self {
+            SidebarMode::Full => SidebarMode::Rail,
+            SidebarMode::Rail => SidebarMode::Hidden,
+            SidebarMode::Hidden => SidebarMode::Full,
+        }
+    }
+}
+
+impl ListDensity {
+    pub const ALL: [ListDensity; 2] = [ListDensity::Cards, ListDensity::Compact];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ListDensity::Cards => "Cards",
+            ListDensity::Compact => "Compact list",
+        }
+    }
+}
+
+impl KeymapPreset {
+    pub const ALL: [KeymapPreset; 2] = [KeymapPreset::Default, KeymapPreset::Vim];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            KeymapPreset::Default => "Default",
+            KeymapPreset::Vim => "Vim (h/j/k/l)",
+        }
+    }
+}
+
+impl IconTheme {
+    pub const ALL: [IconTheme; 2] = [IconTheme::Default, IconTheme::Compatible];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IconTheme::Default => "Emoji icons",
+            IconTheme::Compatible => "ASCII icons (compatible)",
+        }
+    }
+
+    pub fn dir_icon(self) -> &'static str {
+        match self {
+            IconTheme::Default => "📁",
+            IconTheme::Compatible => "[DIR]",
+        }
+    }
+
+    pub fn cmd_icon(self) -> &'static str {
+        match self {
+            IconTheme::Default => "⚙️",
+            IconTheme::Compatible => "[CMD]",
+        }
+    }
+
+    pub fn multi_select_icon(self) -> &'static str {
+        match self {
+            IconTheme::Default => " ✅",
+            IconTheme::Compatible => " *",
+        }
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            timestamp_output: false,
+            output_encoding: OutputEncoding::default(),
+            max_output_mb: 4,
+            save_full_output: true,
+            auto_close_output: false,
+            show_toasts: true,
+            output_dock: false,
+            output_dock_max: 20,
+            toast_secs: 4,
+            output_streams: OutputStreams::default(),
+            tab_order: Vec::new(),
+            hidden_tabs: BTreeSet::new(),
+            max_parallel: 1,
+            stop_on_failure: false,
+            retry_attempts: 0,
+            retry_delay_secs: 5,
+            validate: false,
+            skip_confirmation: false,
+            safe_mode: false,
+            disk_space_check: true,
+            min_free_space_mb: 1024,
+            confirm_close: true,
+            idle_reset_mins: 0,
+            skip_confirm_for: BTreeSet::new(),
+            env: BTreeMap::new(),
+            env_profiles: BTreeMap::new(),
+            env_profile: String::new(),
+            command_env: BTreeMap::new(),
+            command_dir: String::new(),
+            shell: String::new(),
+            command_timeout_secs: 0,
+            exec_log: String::new(),
+            scripts_dir: String::new(),
+            language: String::new(),
+            theme: ThemeVariant::default(),
+            accent_color: None,
+            light_mode: false,
+            icon_theme: IconTheme::default(),
+            markdown_descriptions: true,
+            size_bypass: false,
+            sidebar: SidebarMode::default(),
+            list_density: ListDensity::default(),
+            keymap_preset: KeymapPreset::default(),
+            keybindings: BTreeMap::new(),
+            redact_patterns: [
+                "token",
+                "password",
+                "passwd",
+                "secret",
+                "key",
+                "auth",
+                "credential",
+                "cookie",
+            ]
+            .map(String::from)
+            .to_vec(),
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("linutil").join("desktop.json"))
+    }
+
+    /// Loads the config file, falling back to defaults when it is missing or invalid
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            return Self::default();
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                eprintln!("Failed to parse config file {}: {}", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No config directory found"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// The variables set for a command on top of the inherited environment. A command's own
+    /// variables win over those of the active profile, which win over the global ones, which win
+    /// over whatever the app was started with.
+    pub fn env_for(&self, command_path: &str) -> BTreeMap<String, String> {
+        let mut env = self.env.clone();
+        if let Some(profile) = self.env_profiles.get(&self.env_profile) {
+            env.extend(profile.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        if let Some(overrides) = self.command_env.get(command_path) {
+            env.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        env
+    }
+
+    pub fn retry_policy(&self) -> crate::queue::RetryPolicy {
+        crate::queue::RetryPolicy {
+            attempts: self.retry_attempts,
+            base_delay: std::time::Duration::from_secs(self.retry_delay_secs),
+        }
+    }
+
+    /// How commands run, apart from their environment and working directory
+    pub fn exec_options(&self) -> crate::exec::ExecOptions {
+        crate::exec::ExecOptions {
+            encoding: self.output_encoding,
+            max_output_bytes: Some(self.max_output_mb as usize * 1024 * 1024),
+            full_output_dir: self
+                .save_full_output
+                .then(|| data_dir().map(|dir| dir.join("output")))
+                .flatten(),
+            shell: Some(self.shell.trim())
+                .filter(|shell| !shell.is_empty())
+                .map(str::to_string),
+            timeout: (self.command_timeout_secs > 0)
+                .then(|| std::time::Duration::from_secs(self.command_timeout_secs)),
+            ..Default::default()
+        }
+    }
+
+    /// Where the scripts of the "My Scripts" tab are looked for
+    pub fn scripts_dir(&self) -> Option<PathBuf> {
+        let dir = self.scripts_dir.trim();
+        if dir.is_empty() {
+            data_dir().map(|dir| dir.join("scripts"))
+        } else {
+            Some(PathBuf::from(dir))
+        }
+    }
+
+    /// Where the audit log of executed commands goes, see the exec_log module
+    pub fn exec_log_path(&self) -> Option<PathBuf> {
+        let path = self.exec_log.trim();
+        if path.is_empty() {
+            crate::exec_log::default_path()
+        } else {
+            Some(PathBuf::from(path))
+        }
+    }
+
+    /// The overridden working directory of inline commands, if any
+    pub fn command_dir(&self) -> Option<PathBuf> {
+        let dir = self.command_dir.trim();
+        (!dir.is_empty()).then(|| PathBuf::from(dir))
+    }
+}
+
+/// Directory for app state that isn't configuration, such as the execution history
+pub fn data_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("linutil"))
+}
+
+/// Directory holding the translations of the UI, see the i18n module
+pub fn locale_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("linutil").join("locales"))
+}
+
+fn config_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
@@ -0,0 +1,205 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+// What a command did to the files it declares it modifies (ListNode::modifies): their content is
+// taken before it runs and compared with what is there afterwards, as a unified line diff.
+
+/// Lines of context around each change
+const CONTEXT: usize = 3;
+/// Cells of the LCS table at most, which is quadratic in the changed lines. Beyond it only the
+/// fact that the file changed is reported
+const MAX_TABLE: usize = 4_000_000;
+
+/// A file as it was before the command ran
+pub struct Snapshot {
+    path: PathBuf,
+    content: Result<Option<String>, String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: PathBuf,
+    /// The unified diff, or what happened instead, e.g. "Unchanged"
+    pub text: String,
+}
+
+pub fn snapshot(paths: &[PathBuf]) -> Vec<Snapshot> {
+    paths
+        .iter()
+        .map(|path| Snapshot {
+            path: path.clone(),
+            content: read(path),
+        })
+        .collect()
+}
+
+/// Compares the snapshots with the files as they are now
+pub fn changes(snapshots: Vec<Snapshot>) -> Vec<FileChange> {
+    snapshots
+        .into_iter()
+        .map(|Snapshot { path, content }| {
+            let text = match (content, read(&path)) {
+                (Err(e), _) => format!("Could not be read before the command ran: {}", e),
+                (_, Err(e)) => format!("Could not be read after the command ran: {}", e),
+                (Ok(None), Ok(None)) => "Does not exist".to_string(),
+                (Ok(before), Ok(after)) if before == after => "Unchanged".to_string(),
+                (Ok(None), Ok(Some(after))) => format!("Created\n{}", diff_or_notice("", &after)),
+                (Ok(Some(_)), Ok(None)) => "Deleted".to_string(),
+                (Ok(Some(before)), Ok(Some(after))) => diff_or_notice(&before, &after),
+            };
+            FileChange { path, text }
+        })
+        .collect()
+}
+
+// None when the file doesn't exist
+fn read(path: &Path) -> Result<Option<String>, String> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) if e.kind() == io::ErrorKind::InvalidData => Err("Not a text file".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+fn diff_or_notice(before: &str, after: &str) -> String {
+    unified(before, after).unwrap_or_else(|| "Changed, but too much to show a diff of".to_string())
+}
+
+/// Unified diff of two texts, without the file header. None when the changed part is too large
+pub fn unified(before: &str, after: &str) -> Option<String> {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+
+    // Edits of config files are usually a few lines, so the common start and end are taken off
+    // before building the table
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    if (a.len() + 1).saturating_mul(b.len() + 1) > MAX_TABLE {
+        return None;
+    }
+
+    // lcs[i * width + j]: length of the longest common subsequence of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lcs = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i * width + j] = if a[i] == b[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut ops: Vec<(char, &str)> = old[..prefix].iter().map(|line| (' ', *line)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        // Removals first, as diff(1) does
+        } else if i < a.len()
+            && (j == b.len() || lcs[(i + 1) * width + j] >= lcs[i * width + j + 1])
+        {
+            ops.push(('-', a[i]));
+            i += 1;
+        } else {
+            ops.push(('+', b[j]));
+            j += 1;
+        }
+    }
+    ops.extend(old[old.len() - suffix..].iter().map(|line| (' ', *line)));
+    Some(hunks(&ops))
+}
+
+// Groups the changes into hunks with CONTEXT lines around them; changes closer than that share one
+fn hunks(ops: &[(char, &str)]) -> String {
+    let changed: Vec<usize> = (0..ops.len()).filter(|&i| ops[i].0 != ' ').collect();
+    let mut text = String::new();
+    let mut k = 0;
+    while k < changed.len() {
+        let start = changed[k].saturating_sub(CONTEXT);
+        let mut last = changed[k];
+        while k + 1 < changed.len() && changed[k + 1] - last <= 2 * CONTEXT + 1 {
+            k += 1;
+            last = changed[k];
+        }
+        k += 1;
+        let end = (last + CONTEXT + 1).min(ops.len());
+
+        let count =
+            |ops: &[(char, &str)], skip: char| ops.iter().filter(|(op, _)| *op != skip).count();
+        // A range starts at its first line, or at the line before it when it is empty, as in
+        // "@@ -0,0 +1,2 @@" for lines added to an empty file
+        let range = |skip: char| {
+            let (before, len) = (count(&ops[..start], skip), count(&ops[start..end], skip));
+            format!("{},{}", if len == 0 { before } else { before + 1 }, len)
+        };
+        text.push_str(&format!("@@ -{} +{} @@\n", range('+'), range('-')));
+        for (op, line) in &ops[start..end] {
+            text.push_str(&format!("{}{}\n", op, line));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_into_an_empty_file() {
+        assert_eq!(unified("", "x\ny\n").unwrap(), "@@ -0,0 +1,2 @@\n+x\n+y\n");
+    }
+
+    #[test]
+    fn deletes_everything() {
+        assert_eq!(unified("x\ny\n", "").unwrap(), "@@ -1,2 +0,0 @@\n-x\n-y\n");
+    }
+
+    #[test]
+    fn unchanged_text_has_no_hunks() {
+        assert_eq!(unified("a\nb\n", "a\nb\n").unwrap(), "");
+    }
+
+    #[test]
+    fn mixed_changes_match_diff_u() {
+        let before: String = (1..=20).map(|n| format!("{}\n", n)).collect();
+        let after = before.replace("\n2\n", "\ntwo\n").replace("\n15\n", "\n") + "21\n";
+        // As printed by diff -u
+        let expected = "\
+@@ -1,5 +1,5 @@
+ 1
+-2
++two
+ 3
+ 4
+ 5
+@@ -12,9 +12,9 @@
+ 12
+ 13
+ 14
+-15
+ 16
+ 17
+ 18
+ 19
+ 20
++21
+";
+        assert_eq!(unified(&before, &after).unwrap(), expected);
+    }
+}
@@ -0,0 +1,705 @@
+use crate::{config::OutputEncoding, format_duration, resolve_command, CommandResult, OutputLine};
+use linutil_core::{Command as LinutilCommand, ListNode};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    io::{BufRead, BufReader, Read, Write},
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+// Running commands: building the process of a node, running it to completion while its output is
+// captured, and killing what is still running when a run is stopped or the app closes. Every
+// caller, the GUI worker as well as the command line, goes through run_node.
+
+// Everything about how a command runs besides the command itself. The GUI worker and --execute
+// both go through run_node with it
+#[derive(Clone, Default)]
+pub struct ExecOptions {
+    /// Shell running inline commands, sh when None. Scripts name their own interpreter
+    pub shell: Option<String>,
+    /// Variables set on top of the inherited environment, see AppConfig::env_for
+    pub env: BTreeMap<String, String>,
+    /// Working directory of inline commands, see AppConfig::command_dir
+    pub cwd: Option<PathBuf>,
+    pub encoding: OutputEncoding,
+    /// Kill the command once it has run this long
+    pub timeout: Option<Duration>,
+    /// Report what would run instead of running it
+    pub dry_run: bool,
+    /// Output kept in memory; older lines are dropped beyond it. Unlimited when None
+    pub max_output_bytes: Option<usize>,
+    /// Where the complete output goes once it exceeds max_output_bytes
+    pub full_output_dir: Option<PathBuf>,
+}
+
+// User variables are applied after DEBIAN_FRONTEND, so they can override it as well
+fn raw_process(cmd: &str, opts: &ExecOptions) -> Command {
+    let mut command = Command::new(opts.shell.as_deref().unwrap_or("sh"));
+    command
+        .arg("-c")
+        .arg(cmd)
+        .env("DEBIAN_FRONTEND", "noninteractive")
+        .envs(&opts.env);
+    if let Some(cwd) = &opts.cwd {
+        command.current_dir(cwd);
+    }
+    command
+}
+
+// The script path is made absolute, since the process runs from the script's directory and a path
+// relative to the app's directory would no longer resolve
+pub fn absolute_script_args(
+    args: &[String],
+    file: &std::path::Path,
+) -> (std::path::PathBuf, Vec<String>) {
+    let absolute = std::fs::canonicalize(file)
+        .or_else(|_| std::path::absolute(file))
+        .unwrap_or_else(|_| file.to_path_buf());
+    let args = args
+        .iter()
+        .map(|arg| {
+            if std::path::Path::new(arg) == file {
+                absolute.to_string_lossy().to_string()
+            } else {
+                arg.clone()
+            }
+        })
+        .collect();
+    (absolute, args)
+}
+
+fn script_process(
+    executable: &str,
+    args: &[String],
+    file: &std::path::Path,
+    env: &BTreeMap<String, String>,
+) -> Command {
+    let (script, args) = absolute_script_args(args, file);
+    // Running from the script's directory keeps its relative includes, like `source ./common.sh`,
+    // working
+    let script_dir = script.parent().unwrap_or_else(|| std::path::Path::new("."));
+
+    let mut command = Command::new(executable);
+    command
+        .args(args)
+        .current_dir(script_dir)
+        .env("DEBIAN_FRONTEND", "noninteractive")
+        .envs(env);
+    command
+}
+
+pub fn build_process(command: &LinutilCommand, opts: &ExecOptions) -> Option<Command> {
+    match command {
+        LinutilCommand::Raw(cmd) => Some(raw_process(cmd, opts)),
+        LinutilCommand::LocalFile {
+            executable,
+            args,
+            file,
+        } => Some(script_process(executable, args, file, &opts.env)),
+        LinutilCommand::None => None,
+    }
+}
+
+// The directory the process will start in
+pub fn working_dir(process: &Command) -> PathBuf {
+    match process.get_current_dir() {
+        Some(dir) => std::fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf()),
+        None => std::env::current_dir().unwrap_or_default(),
+    }
+}
+
+// Runs a command to completion and captures its output. Nothing here touches the UI, the caller
+// decides where the result goes
+pub fn run_node(node: &ListNode, opts: &ExecOptions) -> CommandResult {
+    let Some(mut command) = build_process(&node.command, opts) else {
+        return CommandResult::not_run(
+            "Cannot execute directory".to_string(),
+            Some("This is a directory, not an executable command".to_string()),
+            PathBuf::new(),
+        );
+    };
+    let is_raw = matches!(node.command, LinutilCommand::Raw(_));
+    let kind = if is_raw { "Command" } else { "Script" };
+    // A missing directory would otherwise surface as a confusing "No such file or directory" for sh
+    if let Some(cwd) = opts.cwd.as_deref().filter(|cwd| is_raw && !cwd.is_dir()) {
+        let error = format!("Working directory {} does not exist", cwd.display());
+        return CommandResult::not_run(error.clone(), Some(error), cwd.to_path_buf());
+    }
+    let working_dir = working_dir(&command);
+    if opts.dry_run {
+        let line = resolve_command(&node.command, opts)
+            .map(|resolved| resolved.line)
+            .unwrap_or_default();
+        return CommandResult::not_run(format!("Would run: {}", line), None, working_dir);
+    }
+
+    match capture_output(&mut command, opts) {
+        Ok(captured) => {
+            let success = captured.status.success() && !captured.timed_out;
+            let result_output = if captured.stdout.is_empty() && !captured.stderr.is_empty() {
+                captured.stderr.clone()
+            } else if !captured.stdout.is_empty() {
+                captured.stdout.clone()
+            } else {
+                empty_output_message(kind, captured.status)
+            };
+            let error = match opts.timeout {
+                Some(timeout) if captured.timed_out => {
+                    Some(format!("Timed out after {}", format_duration(timeout)))
+                }
+                _ if success => None,
+                _ => Some(captured.stderr.clone()),
+            };
+
+            CommandResult {
+                success,
+                output: result_output,
+                error,
+                stdout: captured.stdout,
+                stderr: captured.stderr,
+                lines: captured.lines,
+                started_at: captured.started_at,
+                elapsed: captured.elapsed,
+                working_dir,
+                exit_code: captured.status.code(),
+                failed_attempts: Vec::new(),
+                dropped_lines: captured.dropped_lines,
+                full_output: captured.full_output,
+                file_changes: Vec::new(),
+                verification: None,
+            }
+        }
+        Err(e) => CommandResult::not_run(
+            format!("Failed to execute {}: {}", kind.to_lowercase(), e),
+            Some(e.to_string()),
+            working_dir,
+        ),
+    }
+}
+
+impl CommandResult {
+    // Result of a command that never started, successful only for a dry run
+    fn not_run(output: String, error: Option<String>, working_dir: PathBuf) -> Self {
+        CommandResult {
+            success: error.is_none(),
+            output,
+            error,
+            stdout: String::new(),
+            stderr: String::new(),
+            lines: Vec::new(),
+            started_at: SystemTime::now(),
+            elapsed: Duration::ZERO,
+            working_dir,
+            exit_code: None,
+            failed_attempts: Vec::new(),
+            dropped_lines: 0,
+            full_output: None,
+            file_changes: Vec::new(),
+            verification: None,
+        }
+    }
+}
+
+struct CapturedOutput {
+    status: ExitStatus,
+    stdout: String,
+    stderr: String,
+    lines: Vec<OutputLine>,
+    started_at: SystemTime,
+    elapsed: Duration,
+    /// Killed for running longer than the timeout
+    timed_out: bool,
+    dropped_lines: usize,
+    full_output: Option<PathBuf>,
+}
+
+// The newest lines of a command's output, within a size limit. Once the limit is hit, everything
+// is also written to a file if a directory for that was given.
+struct OutputTail {
+    lines: VecDeque<OutputLine>,
+    bytes: usize,
+    limit: Option<usize>,
+    dropped: usize,
+    full_output_dir: Option<PathBuf>,
+    full_output: Option<(PathBuf, std::io::BufWriter<std::fs::File>)>,
+}
+
+impl OutputTail {
+    fn new(opts: &ExecOptions) -> Self {
+        Self {
+            lines: VecDeque::new(),
+            bytes: 0,
+            limit: opts.max_output_bytes,
+            dropped: 0,
+            full_output_dir: opts.full_output_dir.clone(),
+            full_output: None,
+        }
+    }
+
+    fn push(&mut self, line: OutputLine) {
+        self.bytes += line.text.len() + 1;
+        self.lines.push_back(line);
+        let Some(limit) = self.limit else {
+            return;
+        };
+        if let Some((path, file)) = &mut self.full_output {
+            let line = self.lines.back().map_or("", |line| &line.text);
+            if let Err(e) = writeln!(file, "{}", line) {
+                eprintln!(
+                    "Failed to write the full output to {}: {}",
+                    path.display(),
+                    e
+                );
+                self.full_output = None;
+            }
+        } else if self.bytes > limit {
+            // Lines are about to be lost, so everything up to here goes to the file first
+            if let Some(dir) = self.full_output_dir.take() {
+                match self.create_full_output(&dir) {
+                    Ok(file) => self.full_output = Some(file),
+                    Err(e) => {
+                        eprintln!("Failed to save the full output in {}: {}", dir.display(), e)
+                    }
+                }
+            }
+        }
+        while self.bytes > limit && self.lines.len() > 1 {
+            if let Some(dropped) = self.lines.pop_front() {
+                self.bytes -= dropped.text.len() + 1;
+                self.dropped += 1;
+            }
+        }
+    }
+
+    fn create_full_output(
+        &self,
+        dir: &Path,
+    ) -> std::io::Result<(PathBuf, std::io::BufWriter<std::fs::File>)> {
+        std::fs::create_dir_all(dir)?;
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_millis());
+        let path = dir.join(format!("output-{}.log", started));
+        let mut file = std::io::BufWriter::new(std::fs::File::create(&path)?);
+        for line in &self.lines {
+            writeln!(file, "{}", line.text)?;
+        }
+        Ok((path, file))
+    }
+}
+
+// Set when the app closes, stops the worker from starting further jobs
+pub static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+// Set by "Stop" until the stopped run has been reported, keeps the worker from starting anything
+pub static STOPPING: AtomicBool = AtomicBool::new(false);
+// Set by the GUI. Headless runs leave commands in the terminal's process group, so that Ctrl+C
+// still reaches them
+pub static OWN_PROCESS_GROUP: AtomicBool = AtomicBool::new(false);
+// Processes of the running commands by pid, so that they can be killed when the app closes
+pub static RUNNING_CHILDREN: Mutex<BTreeMap<u32, Child>> = Mutex::new(BTreeMap::new());
+
+pub fn stop_requested() -> bool {
+    SHUTTING_DOWN.load(Ordering::Relaxed) || STOPPING.load(Ordering::Relaxed)
+}
+
+// Commands of the GUI run in their own process group, so killing the group takes whatever they
+// started down with them and nothing is left holding the output pipes open
+pub fn kill_process_group(mut child: Child) {
+    send_kill(&mut child);
+    let _ = child.wait();
+}
+
+// Without a process group of its own (headless runs) only the child itself is killed
+pub fn send_kill(child: &mut Child) {
+    // A group leader's pid is its group id. The kernel doesn't hand out a pid while a group of
+    // that id exists, so this can't hit anything but the child's own group
+    let killed = unsafe { libc::killpg(child.id() as libc::pid_t, libc::SIGKILL) } == 0;
+    if !killed {
+        let _ = child.kill();
+    }
+}
+
+// How long the output of a killed command is still read
+const KILL_GRACE: Duration = Duration::from_secs(1);
+// How often reading the output checks whether the run was stopped
+const STOP_POLL: Duration = Duration::from_millis(100);
+
+// Runs the command to completion, reading stdout and stderr line by line so that every line can be
+// stamped with the time it arrived
+fn capture_output(command: &mut Command, opts: &ExecOptions) -> std::io::Result<CapturedOutput> {
+    let (encoding, timeout) = (opts.encoding, opts.timeout);
+    let started_at = SystemTime::now();
+    let start = Instant::now();
+    if OWN_PROCESS_GROUP.load(Ordering::Relaxed) {
+        command.process_group(0);
+    }
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let (line_tx, line_rx) = mpsc::channel();
+    if let Some(stdout) = child.stdout.take() {
+        spawn_line_reader(stdout, false, start, encoding, line_tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        spawn_line_reader(stderr, true, start, encoding, line_tx.clone());
+    }
+    // Drop our own sender so the loop below ends once both readers hit EOF
+    drop(line_tx);
+    let pid = child.id();
+    RUNNING_CHILDREN.lock().unwrap().insert(pid, child);
+    // The app may have closed, or the run been stopped, between spawning and registering the child
+    if stop_requested() {
+        if let Some(child) = RUNNING_CHILDREN.lock().unwrap().remove(&pid) {
+            kill_process_group(child);
+        }
+    }
+
+    let mut tail = OutputTail::new(opts);
+    let mut timed_out = false;
+    // Set once the command has been killed, for its timeout or by stopping the run or closing
+    // the app. Something it started may have left its process group and still hold the pipes
+    // open, so from then on the output is read until the deadline at most
+    let mut killed = false;
+    let mut deadline = timeout.map(|timeout| start + timeout);
+    loop {
+        // Wakes up now and then to notice a stop, which kills the command from another thread
+        let wait = deadline.map_or(STOP_POLL, |at| {
+            at.saturating_duration_since(Instant::now()).min(STOP_POLL)
+        });
+        match line_rx.recv_timeout(wait) {
+            Ok(line) => tail.push(line),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                let passed = deadline.is_some_and(|at| Instant::now() >= at);
+                if killed {
+                    if passed {
+                        break;
+                    }
+                } else if passed {
+                    // Output up to here is kept
+                    timed_out = true;
+                    killed = true;
+                    deadline = Some(Instant::now() + KILL_GRACE);
+                    if let Some(child) = RUNNING_CHILDREN.lock().unwrap().get_mut(&pid) {
+                        send_kill(child);
+                    }
+                } else if stop_requested() {
+                    killed = true;
+                    deadline = Some(Instant::now() + KILL_GRACE);
+                }
+            }
+        }
+    }
+
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    for line in &tail.lines {
+        let buffer = if line.is_stderr {
+            &mut stderr
+        } else {
+            &mut stdout
+        };
+        buffer.push_str(&line.text);
+        buffer.push('\n');
+    }
+    let full_output = tail
+        .full_output
+        .and_then(|(path, mut file)| match file.flush() {
+            Ok(()) => Some(path),
+            Err(e) => {
+                eprintln!(
+                    "Failed to write the full output to {}: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        });
+
+    // Gone from the map when the app killed it on close
+    let Some(mut child) = RUNNING_CHILDREN.lock().unwrap().remove(&pid) else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Interrupted,
+            "Killed because the app was closed",
+        ));
+    };
+    let status = child.wait()?;
+    Ok(CapturedOutput {
+        status,
+        stdout,
+        stderr,
+        lines: tail.lines.into(),
+        started_at,
+        elapsed: start.elapsed(),
+        timed_out,
+        dropped_lines: tail.dropped,
+        full_output,
+    })
+}
+
+fn spawn_line_reader<R: Read + Send + 'static>(
+    reader: R,
+    is_stderr: bool,
+    start: Instant,
+    encoding: OutputEncoding,
+    tx: mpsc::Sender<OutputLine>,
+) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut buf = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+            let line = buf.strip_suffix(b"\n").unwrap_or(&buf);
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            let (text, invalid_utf8) = match std::str::from_utf8(line) {
+                Ok(text) => (text.to_string(), false),
+                Err(_) => (encoding.decode(line), true),
+            };
+            let _ = tx.send(OutputLine {
+                elapsed: start.elapsed(),
+                text,
+                is_stderr,
+                invalid_utf8,
+            });
+            buf.clear();
+        }
+    });
+}
+
+// What the output says when the process printed nothing at all
+fn empty_output_message(kind: &str, status: ExitStatus) -> String {
+    if status.success() {
+        return format!("{} executed successfully", kind);
+    }
+    match status.code() {
+        Some(code) => format!(
+            "{} failed with exit code {} and produced no output",
+            kind, code
+        ),
+        // No exit code means a signal ended the process
+        None => format!(
+            "{} was terminated ({}) and produced no output",
+            kind, status
+        ),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::RwLock;
+
+    /// Tests that set SHUTTING_DOWN hold this for writing, so that it can't kill the commands of
+    /// other tests running at the same time
+    pub static GLOBALS: RwLock<()> = RwLock::new(());
+
+    fn run(node: &ListNode, opts: &ExecOptions) -> CommandResult {
+        let _globals = GLOBALS.read().unwrap_or_else(|e| e.into_inner());
+        run_node(node, opts)
+    }
+
+    pub fn node(command: LinutilCommand) -> ListNode {
+        ListNode {
+            name: "test".to_string(),
+            description: String::new(),
+            command,
+            task_list: String::new(),
+            multi_select: false,
+            requirements: Vec::new(),
+            dependencies: Vec::new(),
+            modifies: Vec::new(),
+            verify: None,
+            danger: false,
+        }
+    }
+
+    fn raw(cmd: &str) -> ListNode {
+        node(LinutilCommand::Raw(cmd.to_string()))
+    }
+
+    #[test]
+    fn captures_the_output_of_a_successful_command() {
+        let result = run(&raw("echo hello"), &ExecOptions::default());
+        assert!(result.success);
+        assert_eq!(result.exit_code, Some(0));
+        assert_eq!(result.stdout, "hello\n");
+        assert_eq!(result.output, "hello\n");
+        assert_eq!(result.error, None);
+    }
+
+    #[test]
+    fn reports_a_non_zero_exit() {
+        let result = run(&raw("echo oops >&2; exit 3"), &ExecOptions::default());
+        assert!(!result.success);
+        assert_eq!(result.exit_code, Some(3));
+        assert_eq!(result.stdout, "");
+        assert_eq!(result.stderr, "oops\n");
+        assert_eq!(result.error.as_deref(), Some("oops\n"));
+    }
+
+    #[test]
+    fn reports_a_command_that_cannot_start() {
+        let script = LinutilCommand::LocalFile {
+            executable: "/nonexistent/interpreter".to_string(),
+            args: Vec::new(),
+            file: std::env::temp_dir().join("linutil-missing-interpreter.sh"),
+        };
+        let result = run(&node(script), &ExecOptions::default());
+        assert!(!result.success);
+        assert_eq!(result.exit_code, None);
+        assert!(
+            result.output.starts_with("Failed to execute script: "),
+            "{}",
+            result.output
+        );
+        assert!(result.error.is_some());
+    }
+
+    #[test]
+    fn explains_a_failure_without_output() {
+        let result = run(&raw("false"), &ExecOptions::default());
+        assert!(!result.success);
+        assert_eq!(result.exit_code, Some(1));
+        assert_eq!(
+            result.output,
+            "Command failed with exit code 1 and produced no output"
+        );
+    }
+
+    #[test]
+    fn kills_a_command_at_its_timeout() {
+        let opts = ExecOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let result = run(&raw("echo started; sleep 30"), &opts);
+        assert!(!result.success);
+        // Killed by a signal
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.stdout, "started\n");
+        assert_eq!(result.error.as_deref(), Some("Timed out after 0.2s"));
+        assert!(
+            result.elapsed < Duration::from_secs(10),
+            "{:?}",
+            result.elapsed
+        );
+    }
+
+    #[test]
+    fn stops_waiting_for_processes_left_behind_by_a_timeout() {
+        let opts = ExecOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        // The background sleep survives killing sh here and keeps the pipes open
+        let result = run(&raw("sleep 5 & wait"), &opts);
+        assert!(!result.success);
+        assert!(
+            result.elapsed < Duration::from_secs(4),
+            "{:?}",
+            result.elapsed
+        );
+    }
+
+    #[test]
+    fn runs_inline_commands_with_the_configured_shell() {
+        let opts = ExecOptions {
+            shell: Some("bash".to_string()),
+            ..Default::default()
+        };
+        let result = run(&raw("echo $0"), &opts);
+        assert_eq!(result.stdout, "bash\n");
+    }
+
+    #[test]
+    fn dry_run_runs_nothing() {
+        let file = std::env::temp_dir().join(format!("linutil-dry-run-{}", std::process::id()));
+        let opts = ExecOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let result = run(&raw(&format!("touch {}", file.display())), &opts);
+        assert!(result.success);
+        assert!(
+            result
+                .output
+                .starts_with("Would run: DEBIAN_FRONTEND=noninteractive sh -c "),
+            "{}",
+            result.output
+        );
+        assert!(!file.exists());
+    }
+
+    // An empty directory of its own for a test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("linutil-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn scripts_can_source_files_next_to_them() {
+        let dir = temp_dir("source");
+        std::fs::write(dir.join("common.sh"), "GREETING=hello\n").unwrap();
+        let script = dir.join("main.sh");
+        std::fs::write(&script, ". ./common.sh\necho \"$GREETING from $(pwd)\"\n").unwrap();
+
+        let command = LinutilCommand::LocalFile {
+            executable: "sh".to_string(),
+            args: vec![script.to_string_lossy().to_string()],
+            file: script,
+        };
+        let result = run(&node(command), &ExecOptions::default());
+        let dir = std::fs::canonicalize(&dir).unwrap();
+        assert!(result.success, "{}", result.output);
+        assert_eq!(result.stdout, format!("hello from {}\n", dir.display()));
+        assert_eq!(result.working_dir, dir);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn script_paths_are_made_absolute() {
+        let dir = temp_dir("absolute");
+        let script = dir.join("main.sh");
+        std::fs::write(&script, "").unwrap();
+        let args = ["-e".to_string(), script.to_string_lossy().to_string()];
+
+        let (absolute, args) = absolute_script_args(&args, &script);
+        let absolute_arg = absolute.to_string_lossy().to_string();
+        assert!(absolute.is_absolute());
+        assert_eq!(args, ["-e".to_string(), absolute_arg]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn does_not_run_a_directory() {
+        let result = run(&node(LinutilCommand::None), &ExecOptions::default());
+        assert!(!result.success);
+        assert_eq!(result.exit_code, None);
+        assert_eq!(result.output, "Cannot execute directory");
+    }
+
+    #[test]
+    fn reports_a_missing_working_directory() {
+        let opts = ExecOptions {
+            cwd: Some(PathBuf::from("/nonexistent/linutil-dir")),
+            ..Default::default()
+        };
+        let result = run(&raw("true"), &opts);
+        assert!(!result.success);
+        assert_eq!(
+            result.error.as_deref(),
+            Some("Working directory /nonexistent/linutil-dir does not exist")
+        );
+    }
+}
@@ -0,0 +1,70 @@
+use crate::{config::data_dir, CommandResult};
+use serde::Serialize;
+use std::{
+    ffi::OsString,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::UNIX_EPOCH,
+};
+
+// Audit log of every executed command, one JSON object per line. Unlike the history it is never
+// read back by the app; it is meant for whoever administers the machine.
+
+/// Past this size the log is moved to "<file>.1", replacing the previous one, so at most twice
+/// this much is kept
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+// Parallel jobs finish on different threads, and rotating must not race with appending
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Serialize)]
+pub struct LogEntry<'a> {
+    /// Unix timestamp (seconds) of when the command started
+    pub started_at: u64,
+    /// Breadcrumb of the command, e.g. "System Setup › Arch Linux › Paru AUR Helper"
+    pub path: &'a str,
+    /// The command line as it would be typed into a terminal
+    pub command: Option<String>,
+    /// None when the process was killed by a signal or never started
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub duration_ms: u64,
+}
+
+impl<'a> LogEntry<'a> {
+    pub fn new(path: &'a str, command: Option<String>, result: &CommandResult) -> Self {
+        Self {
+            started_at: result
+                .started_at
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs()),
+            path,
+            command,
+            exit_code: result.exit_code,
+            success: result.success,
+            duration_ms: result.elapsed.as_millis() as u64,
+        }
+    }
+}
+
+pub fn default_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("commands.log"))
+}
+
+pub fn append(path: &Path, entry: &LogEntry) -> io::Result<()> {
+    let _lock = LOG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if fs::metadata(path).is_ok_and(|metadata| metadata.len() > MAX_LOG_SIZE) {
+        let mut rotated = OsString::from(path.as_os_str());
+        rotated.push(".1");
+        fs::rename(path, rotated)?;
+    }
+
+    let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
@@ -0,0 +1,129 @@
+use crate::ModernTheme;
+use eframe::egui::{
+    text::{LayoutJob, TextFormat},
+    Color32, FontId,
+};
+
+// Past this size the preview starts with highlighting disabled, since the layout job is rebuilt
+// whenever the preview is laid out
+pub const MAX_HIGHLIGHT_BYTES: usize = 256 * 1024;
+
+const KEYWORDS: &[&str] = &[
+    "if", "then", "else", "elif", "fi", "for", "while", "until", "do", "done", "case", "esac",
+    "in", "function", "select", "return", "local", "export", "readonly", "break", "continue",
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum TokenKind {
+    Plain,
+    Comment,
+    String,
+    Keyword,
+    Variable,
+}
+
+impl TokenKind {
+    fn color(self, theme: &ModernTheme) -> Color32 {
+        match self {
+            TokenKind::Plain => theme.on_surface,
+            TokenKind::Comment => theme.on_surface_variant.gamma_multiply(0.6),
+            TokenKind::String => theme.success,
+            TokenKind::Keyword => theme.secondary,
+            TokenKind::Variable => theme.warning,
+        }
+    }
+}
+
+/// Whether the highlighter knows the language of scripts run by this interpreter
+pub fn is_shell(interpreter: &str) -> bool {
+    matches!(interpreter, "sh" | "bash" | "dash" | "ksh" | "zsh" | "ash")
+}
+
+/// Builds a colored layout job for a shell script. This is a deliberately small tokenizer: it only
+/// knows about comments, quoted strings, keywords and variable expansions.
+pub fn highlight_shell(source: &str, theme: &ModernTheme, font_id: FontId) -> LayoutJob {
+    let mut tokens: Vec<(usize, usize, TokenKind)> = Vec::new();
+    let mut prev: Option<char> = None;
+    let mut pos = 0;
+
+    while pos < source.len() {
+        let rest = &source[pos..];
+        let (len, kind) = next_token(rest, prev);
+        let end = pos + len;
+
+        // Merge runs of the same kind, so plain text does not end up as one section per char
+        match tokens.last_mut() {
+            Some((_, last_end, last_kind)) if *last_kind == kind => *last_end = end,
+            _ => tokens.push((pos, end, kind)),
+        }
+
+        prev = source[..end].chars().next_back();
+        pos = end;
+    }
+
+    let mut job = LayoutJob::default();
+    for (start, end, kind) in tokens {
+        job.append(
+            &source[start..end],
+            0.0,
+            TextFormat::simple(font_id.clone(), kind.color(theme)),
+        );
+    }
+    job
+}
+
+fn next_token(rest: &str, prev: Option<char>) -> (usize, TokenKind) {
+    let mut chars = rest.char_indices();
+    let Some((_, first)) = chars.next() else {
+        return (0, TokenKind::Plain);
+    };
+
+    match first {
+        '#' if prev.is_none_or(|c| c.is_whitespace() || c == ';' || c == '(') => {
+            (rest.find('\n').unwrap_or(rest.len()), TokenKind::Comment)
+        }
+        '\'' => (
+            rest[1..].find('\'').map_or(rest.len(), |i| i + 2),
+            TokenKind::String,
+        ),
+        '"' => {
+            let mut escaped = false;
+            for (i, c) in chars {
+                match c {
+                    '\\' if !escaped => escaped = true,
+                    '"' if !escaped => return (i + 1, TokenKind::String),
+                    _ => escaped = false,
+                }
+            }
+            (rest.len(), TokenKind::String)
+        }
+        '$' => match chars.next() {
+            Some((_, '{')) => (
+                rest.find('}').map_or(rest.len(), |i| i + 1),
+                TokenKind::Variable,
+            ),
+            Some((_, '(')) => (2, TokenKind::Variable),
+            Some((_, c)) if c.is_ascii_alphabetic() || c == '_' => {
+                let len = rest[1..]
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .map_or(rest.len(), |i| i + 1);
+                (len, TokenKind::Variable)
+            }
+            Some((_, c)) if c.is_ascii_digit() || "?#@*!$-".contains(c) => (2, TokenKind::Variable),
+            _ => (1, TokenKind::Plain),
+        },
+        c if c.is_alphabetic() || c == '_' => {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '-'))
+                .unwrap_or(rest.len());
+            let word_start = prev.is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '-'));
+            let kind = if word_start && KEYWORDS.contains(&&rest[..len]) {
+                TokenKind::Keyword
+            } else {
+                TokenKind::Plain
+            };
+            (len, kind)
+        }
+        c => (c.len_utf8(), TokenKind::Plain),
+    }
+}
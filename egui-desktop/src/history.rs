@@ -0,0 +1,69 @@
+use crate::config::data_dir;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistoryEntry {
+    /// Breadcrumb of the command, e.g. "System Setup › Arch Linux › Paru AUR Helper"
+    pub path: String,
+    pub name: String,
+    pub success: bool,
+    /// Unix timestamp (seconds) of when the command finished
+    pub finished_at: u64,
+    /// How long the command ran. Entries recorded before this was tracked have 0
+    #[serde(default)]
+    pub duration_ms: u64,
+}
+
+impl HistoryEntry {
+    pub fn new(path: String, name: String, success: bool, duration: Duration) -> Self {
+        let finished_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            path,
+            name,
+            success,
+            finished_at,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+}
+
+// The history is stored as JSON lines so that recording a run is a cheap append
+fn history_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("history.jsonl"))
+}
+
+pub fn load() -> Vec<HistoryEntry> {
+    let Some(content) = history_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+pub fn record(entry: &HistoryEntry) -> io::Result<()> {
+    let path = history_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory found"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry).map_err(io::Error::other)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+pub fn clear() -> io::Result<()> {
+    match history_path().map(fs::remove_file) {
+        Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,87 @@
+use crate::config::locale_dir;
+use std::{collections::HashMap, fmt::Display, fs, sync::OnceLock};
+
+// Translations of the UI strings, keyed by their English text. Strings missing from the table stay
+// in English, so a locale file can be partial.
+static STRINGS: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Loads the string table for `language`, or for the locale from the environment when it is empty.
+/// Only the first call has an effect, switching languages takes a restart.
+pub fn init(language: &str) {
+    let _ = STRINGS.set(load(language));
+}
+
+pub fn tr(text: &str) -> String {
+    STRINGS
+        .get()
+        .and_then(|strings| strings.get(text))
+        .cloned()
+        .unwrap_or_else(|| text.to_string())
+}
+
+/// Translates `text` and fills in its `{0}`, `{1}`, ... placeholders. They are numbered so that a
+/// translation can reorder them.
+pub fn tr_fmt(text: &str, args: &[&dyn Display]) -> String {
+    let mut translated = tr(text);
+    for (i, arg) in args.iter().enumerate() {
+        translated = translated.replace(&format!("{{{}}}", i), &arg.to_string());
+    }
+    translated
+}
+
+/// Languages that have a locale file, e.g. "de" or "pt_BR"
+pub fn available_languages() -> Vec<String> {
+    let Some(entries) = locale_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return Vec::new();
+    };
+    let mut languages: Vec<String> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            Some(path.file_stem()?.to_string_lossy().to_string())
+        })
+        .collect();
+    languages.sort();
+    languages
+}
+
+// Locale files are JSON objects mapping the English strings to their translation, stored as
+// <config dir>/linutil/locales/<language>.json. "de_DE" falls back to "de".
+fn load(language: &str) -> HashMap<String, String> {
+    let language = if language.is_empty() {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default()
+    } else {
+        language.to_string()
+    };
+
+    // Drop the encoding and modifier, as in "de_DE.UTF-8" or "sr_RS@latin"
+    let language = language.split(['.', '@']).next().unwrap_or_default();
+    if language.is_empty() || language == "C" || language == "POSIX" {
+        return HashMap::new();
+    }
+
+    let Some(dir) = locale_dir() else {
+        return HashMap::new();
+    };
+    let mut candidates = vec![language];
+    if let Some((base, _)) = language.split_once('_') {
+        candidates.push(base);
+    }
+    for candidate in candidates {
+        let path = dir.join(format!("{}.json", candidate));
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        match serde_json::from_str(&content) {
+            Ok(strings) => return strings,
+            Err(e) => eprintln!("Failed to parse locale file {}: {}", path.display(), e),
+        }
+    }
+    HashMap::new()
+}
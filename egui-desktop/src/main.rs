@@ -1,10 +1,54 @@
+mod backends;
+mod cli;
+mod config;
+mod diff;
+mod exec;
+mod exec_log;
+mod highlight;
+mod history;
+mod i18n;
+mod markdown;
+mod notes;
+mod palette;
+mod queue;
+mod report;
+mod selftest;
+mod shortcuts;
+mod toast;
+
+use config::{AppConfig, IconTheme, KeymapPreset, ListDensity, OutputEncoding, OutputStreams, SidebarMode, ThemeVariant};
+use exec::{
+    absolute_script_args, build_process, kill_process_group, run_node, send_kill, stop_requested, working_dir, ExecOptions,
+    OWN_PROCESS_GROUP, RUNNING_CHILDREN, SHUTTING_DOWN, STOPPING,
+};
+use history::HistoryEntry;
+use i18n::{tr, tr_fmt};
+use queue::{QueueItem, QueueStatus, QueuedBatch, RetryPolicy, ScheduledJob};
+use shortcuts::Action;
+use toast::Severity;
 use eframe::egui;
-use linutil_core::{get_tabs, Command as LinutilCommand, TabList, ListNode, ego_tree::NodeId};
-use std::process::Command;
-use std::sync::{mpsc, Arc};
+use linutil_core::{scripts_tab, try_get_tabs, Command as LinutilCommand, TabList, ListNode, ego_tree::NodeId};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    mpsc, Arc, Mutex,
+};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 fn main() -> Result<(), eframe::Error> {
+    let safe_mode = match cli::parse(std::env::args().skip(1)) {
+        Ok(cli::Mode::Gui { safe }) => safe,
+        Ok(mode) => std::process::exit(cli::run(mode)),
+        Err(e) => {
+            eprintln!("{}\n{}", e, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1400.0, 900.0])
@@ -16,9 +60,9 @@ fn main() -> Result<(), eframe::Error> {
         "Linutil System Management Suite",
         options,
         Box::new(|cc| {
-            // Set modern dark theme
-            cc.egui_ctx.set_visuals(create_modern_visuals());
-            Ok(Box::new(LinutilApp::new()))
+            let app = LinutilApp::new(safe_mode);
+            cc.egui_ctx.set_visuals(create_modern_visuals(&app.theme));
+            Ok(Box::new(app))
         }),
     )
 }
@@ -38,6 +82,8 @@ struct ModernTheme {
     on_surface: egui::Color32,
     on_surface_variant: egui::Color32,
     border: egui::Color32,
+    /// Surfaces are dark and text is light, which picks egui's dark base visuals
+    dark: bool,
 }
 
 impl ModernTheme {
@@ -55,13 +101,99 @@ impl ModernTheme {
             on_surface: egui::Color32::from_rgb(248, 250, 252), // Slate 50
             on_surface_variant: egui::Color32::from_rgb(203, 213, 225), // Slate 300
             border: egui::Color32::from_rgb(71, 85, 105),       // Slate 600
+            dark: true,
+        }
+    }
+
+    fn from_config(config: &AppConfig) -> Self {
+        let mut theme = Self::for_variant(config.theme);
+        if config.light_mode {
+            theme = theme.light(config.theme);
+        }
+        if let Some([r, g, b]) = config.accent_color {
+            theme.primary = egui::Color32::from_rgb(r, g, b);
+            theme.accent = theme.primary;
+        }
+        theme
+    }
+
+    fn for_variant(variant: ThemeVariant) -> Self {
+        match variant {
+            ThemeVariant::Modern => Self::new(),
+            ThemeVariant::HighContrast => Self::high_contrast(),
+            ThemeVariant::ColorBlind => Self::color_blind(),
+        }
+    }
+
+    // Bright, saturated colors on black, with light borders so every surface stands out
+    fn high_contrast() -> Self {
+        Self {
+            primary: egui::Color32::from_rgb(0, 200, 255),      // Cyan
+            secondary: egui::Color32::from_rgb(255, 110, 255),  // Magenta
+            accent: egui::Color32::from_rgb(0, 200, 255),       // Cyan
+            success: egui::Color32::from_rgb(0, 255, 120),      // Green
+            warning: egui::Color32::from_rgb(255, 230, 0),      // Yellow
+            danger: egui::Color32::from_rgb(255, 90, 90),       // Red
+            background: egui::Color32::BLACK,
+            surface: egui::Color32::from_rgb(12, 12, 12),
+            surface_variant: egui::Color32::from_rgb(45, 45, 45),
+            on_surface: egui::Color32::WHITE,
+            on_surface_variant: egui::Color32::from_rgb(235, 235, 235),
+            border: egui::Color32::from_rgb(200, 200, 200),
+            dark: true,
         }
     }
+
+    // Swaps the surfaces and text for light ones and keeps the accent colors of the variant
+    fn light(self, variant: ThemeVariant) -> Self {
+        if variant == ThemeVariant::HighContrast {
+            return Self {
+                background: egui::Color32::WHITE,
+                surface: egui::Color32::from_rgb(245, 245, 245),
+                surface_variant: egui::Color32::from_rgb(215, 215, 215),
+                on_surface: egui::Color32::BLACK,
+                on_surface_variant: egui::Color32::from_rgb(20, 20, 20),
+                border: egui::Color32::from_rgb(40, 40, 40),
+                // The bright cyan of the dark variant is unreadable on white
+                primary: egui::Color32::from_rgb(0, 90, 200),
+                accent: egui::Color32::from_rgb(0, 90, 200),
+                dark: false,
+                ..self
+            };
+        }
+        Self {
+            background: egui::Color32::from_rgb(248, 250, 252),   // Slate 50
+            surface: egui::Color32::from_rgb(241, 245, 249),      // Slate 100
+            surface_variant: egui::Color32::from_rgb(226, 232, 240), // Slate 200
+            on_surface: egui::Color32::from_rgb(15, 23, 42),      // Slate 900
+            on_surface_variant: egui::Color32::from_rgb(51, 65, 85), // Slate 700
+            border: egui::Color32::from_rgb(203, 213, 225),       // Slate 300
+            dark: false,
+            ..self
+        }
+    }
+
+    // Okabe-Ito colors: success and danger differ in lightness and hue (blue vs. orange) rather
+    // than only red vs. green
+    fn color_blind() -> Self {
+        Self {
+            primary: egui::Color32::from_rgb(86, 180, 233),     // Sky blue
+            secondary: egui::Color32::from_rgb(204, 121, 167),  // Reddish purple
+            accent: egui::Color32::from_rgb(86, 180, 233),      // Sky blue
+            success: egui::Color32::from_rgb(0, 114, 178),      // Blue
+            warning: egui::Color32::from_rgb(240, 228, 66),     // Yellow
+            danger: egui::Color32::from_rgb(230, 159, 0),       // Orange
+            ..Self::new()
+        }
+    }
+}
+
+fn search_id() -> egui::Id {
+    egui::Id::new("search_box")
 }
 
-fn create_modern_visuals() -> egui::Visuals {
-    let theme = ModernTheme::new();
-    let mut visuals = egui::Visuals::dark();
+fn create_modern_visuals(theme: &ModernTheme) -> egui::Visuals {
+    let mut visuals = if theme.dark { egui::Visuals::dark() } else { egui::Visuals::light() };
     
     // Modern color scheme
     visuals.window_fill = theme.background;
@@ -109,7 +241,198 @@ fn create_modern_visuals() -> egui::Visuals {
 pub struct ListEntry {
     pub node: Arc<ListNode>,
     pub id: NodeId,
-    pub has_children: bool,
+    /// Index of the tab the entry belongs to, which differs from the current one for results of
+    /// an "All tabs" search
+    pub tab: usize,
+    /// Breadcrumb of the entry, as used by the history
+    pub path: String,
+    /// Opens rather than runs: it has children, or no command at all (an empty directory)
+    pub is_directory: bool,
+}
+
+impl ListEntry {
+    /// Whether both are the same node of the same tab. Identical commands in different places
+    /// are different entries
+    pub fn is_same(&self, other: &ListEntry) -> bool {
+        self.tab == other.tab && self.id == other.id
+    }
+}
+
+// How the last run went, for coloring the status bar
+#[derive(Clone, Copy)]
+enum RunOutcome {
+    Succeeded,
+    /// Everything that ran succeeded, but only after retries or with commands skipped
+    Partial,
+    Failed,
+}
+
+// Where the search box looks for matches
+#[derive(Clone, Copy, PartialEq)]
+enum SearchScope {
+    Folder,
+    Tab,
+    AllTabs,
+}
+
+impl SearchScope {
+    const ALL: [SearchScope; 3] = [SearchScope::Folder, SearchScope::Tab, SearchScope::AllTabs];
+
+    fn label(self) -> &'static str {
+        match self {
+            SearchScope::Folder => "This folder",
+            SearchScope::Tab => "This tab",
+            SearchScope::AllTabs => "All tabs",
+        }
+    }
+}
+
+pub struct ScriptPreview {
+    pub path: String,
+    pub name: String,
+    pub description: String,
+    pub task_list: String,
+    pub command_line: Option<String>,
+    // Directory the command runs from
+    pub working_dir: Option<PathBuf>,
+    // File of a script command, for showing it in the file manager
+    pub script: Option<PathBuf>,
+    // What runs a script command, e.g. "python3", and whether its shebang says so
+    pub interpreter: Option<(String, bool)>,
+    // Declared dependencies and whether each is installed
+    pub dependencies: Vec<(String, bool)>,
+    // Command that checks the command worked, run after it succeeded
+    pub verify: Option<String>,
+    pub source: String,
+    // Built lazily the first time the preview is shown with highlighting on
+    pub highlighted: Option<egui::text::LayoutJob>,
+    // New per-command environment variable being typed in
+    pub env_draft: (String, String),
+    // Full definition of the command, for "Copy as JSON"
+    pub json: String,
+    // Entries of a directory, shown instead of the source. Names of subdirectories end in "/"
+    pub contents: Option<Vec<String>>,
+}
+
+// A set of commands to run together. They run in order, unless max_parallel allows several of them
+// to run at the same time
+pub struct CommandBatch {
+    pub jobs: Vec<Job>,
+    pub max_parallel: usize,
+}
+
+pub struct Job {
+    // Assigned when the job is queued, ties the worker's events to the queue panel
+    pub id: u64,
+    pub path: String,
+    pub node: Arc<ListNode>,
+    // Variables set on top of the inherited environment, see AppConfig::env_for
+    pub env: BTreeMap<String, String>,
+    // Working directory override for inline commands, see AppConfig::command_dir
+    pub cwd: Option<PathBuf>,
+}
+
+impl Job {
+    // How the job runs in a batch running with the given options. The worker and the confirmation
+    // dialog both go through here, so the dialog shows what actually runs
+    fn exec_options(&self, batch: &ExecOptions) -> ExecOptions {
+        ExecOptions { env: self.env.clone(), cwd: self.cwd.clone(), ..batch.clone() }
+    }
+}
+
+// Settings of the schedule dialog for one command
+struct ScheduleDraft {
+    job: Job,
+    // Dangerous commands are confirmed with their name here, as nobody may be around when they run
+    typed_name: String,
+    at_time: bool,
+    delay_minutes: u32,
+    hour: u32,
+    minute: u32,
+}
+
+// A batch waiting in the confirmation dialog, with each job resolved to the process it will run as
+struct PendingRun {
+    batch: CommandBatch,
+    resolved: Vec<Option<ResolvedCommand>>,
+    // Declared dependencies of each job that are not installed
+    missing: Vec<Vec<String>>,
+    // Started from "Execute All", so the selection is cleared once the batch is confirmed
+    from_selection: bool,
+    // What has been typed to confirm each job; only dangerous jobs ask for it
+    typed_names: Vec<String>,
+    // Jobs ticked "Don't ask again for this command"
+    dont_ask: Vec<bool>,
+    // Free bytes on the root filesystem, when the batch installs something and the check is on
+    free_space: Option<u64>,
+    // Package manager dry runs of each job, for the operations its body spells out
+    dry_runs: Vec<Vec<DryRun>>,
+}
+
+// A package operation run in its dry-run mode, to show what it would install or remove. Runs on a
+// thread of its own, so slow package managers don't hold up the dialog
+struct DryRun {
+    line: String,
+    rx: mpsc::Receiver<String>,
+    output: Option<String>,
+}
+
+impl DryRun {
+    fn start(args: Vec<String>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let line = args.join(" ");
+        thread::spawn(move || {
+            let output = match Command::new(&args[0]).args(&args[1..]).stdin(Stdio::null()).output() {
+                Ok(output) => format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)),
+                Err(e) => format!("Failed to run {}: {}", args[0], e),
+            };
+            let _ = tx.send(output);
+        });
+        Self { line, rx, output: None }
+    }
+
+    // Whether the output is there, picking it up when it just arrived
+    fn poll(&mut self) -> bool {
+        if self.output.is_none() {
+            self.output = self.rx.try_recv().ok();
+        }
+        self.output.is_some()
+    }
+}
+
+impl PendingRun {
+    // Dangerous commands only run once their name has been typed in
+    fn confirmed(&self) -> bool {
+        self.batch.jobs.iter()
+            .zip(&self.typed_names)
+            .all(|(job, typed)| !job.node.danger || typed.trim() == job.node.name)
+    }
+}
+
+// The exact process behind a command: its full command line plus the parts it is built from
+struct ResolvedCommand {
+    line: String,
+    details: Vec<(&'static str, String)>,
+}
+
+// What the worker reports back about each job
+pub enum CommandEvent {
+    Started { id: u64 },
+    /// Failed, and will run again after the delay; attempt is the one about to start
+    Retrying { id: u64, attempt: u32, delay: Duration },
+    Finished { id: u64, path: String, node: Arc<ListNode>, result: Box<CommandResult> },
+    /// Not run, because an earlier job of a stop-on-failure batch failed or the run was stopped
+    Skipped { id: u64, node: Arc<ListNode> },
+}
+
+#[derive(Debug, Clone)]
+pub struct OutputLine {
+    /// Time since the command was started
+    pub elapsed: Duration,
+    pub text: String,
+    pub is_stderr: bool,
+    /// The line was not valid UTF-8 and had to be decoded with the fallback encoding
+    pub invalid_utf8: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -117,65 +440,297 @@ pub struct CommandResult {
     pub success: bool,
     pub output: String,
     pub error: Option<String>,
+    pub stdout: String,
+    pub stderr: String,
+    /// Every line of stdout and stderr, in the order they arrived
+    pub lines: Vec<OutputLine>,
+    pub started_at: SystemTime,
+    pub elapsed: Duration,
+    /// Directory the command ran in
+    pub working_dir: PathBuf,
+    /// None when the process was killed by a signal or never started
+    pub exit_code: Option<i32>,
+    /// Earlier runs of a retried command, oldest first. Empty unless it failed and was retried
+    pub failed_attempts: Vec<CommandResult>,
+    /// Lines dropped from the start of the output to stay within ExecOptions::max_output_bytes
+    pub dropped_lines: usize,
+    /// File with the complete output, when lines were dropped and saving it was enabled
+    pub full_output: Option<PathBuf>,
+    /// What the command did to the files it declares it modifies
+    pub file_changes: Vec<diff::FileChange>,
+    /// Outcome of the node's verify command. None when it has none or didn't succeed
+    pub verification: Option<Verification>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Verification {
+    pub command: String,
+    pub passed: bool,
+    /// Everything it printed, stdout and stderr
+    pub output: String,
 }
 
+// Directories with more entries than this filter after typing pauses for SEARCH_DEBOUNCE instead of
+// on every keystroke
+const DEBOUNCE_ITEMS: usize = 200;
+const SEARCH_DEBOUNCE: Duration = Duration::from_millis(150);
+// Length of the "Recent" menu in the top bar
+const MAX_RECENT: usize = 10;
+// Below this the layout gets cramped and breaks, see AppConfig::size_bypass
+const MIN_WINDOW_SIZE: egui::Vec2 = egui::vec2(800.0, 600.0);
+// How long closing the app waits for the worker to wind down
+const WORKER_SHUTDOWN_WAIT: Duration = Duration::from_secs(2);
+const SAFE_MODE_HINT: &str = "Execution is disabled in safe mode";
+// How long the status bar keeps the color of the last run's outcome
+const STATUS_OUTCOME_DURATION: Duration = Duration::from_secs(8);
+
 struct LinutilApp {
     // Core data
     tabs: TabList,
     current_tab_index: usize,
     theme: ModernTheme,
+    config: AppConfig,
+    // Keyboard shortcuts with the preset and keybindings of the config applied
+    keymap: shortcuts::Keymap,
+    // Set by --safe. Unlike AppConfig::safe_mode it only lasts for this session, see safe_mode()
+    forced_safe_mode: bool,
     
     // Navigation state (like TUI's visit_stack)
     visit_stack: Vec<(NodeId, usize)>, // (node_id, selection_index)
+    // Package managers found on PATH at startup
+    package_managers: Vec<backends::PackageManager>,
+    // Commands written for a package manager that is not installed, by path. Rebuilt on load
+    missing_backends: HashMap<String, backends::PackageManager>,
+    // Rebuilt by update_items, which runs whenever visit_stack or the current tab change
+    breadcrumb: String,
     current_items: Vec<ListEntry>,
+    // The first folder_len of current_items are the children of the current folder. The rest are
+    // the other entries of the search scope, only shown while searching
+    folder_len: usize,
+    // Shared copies of the nodes, by tab index and node id. Core keeps its nodes in an Rc, which
+    // can't be handed to the worker, so each node is copied into an Arc once and reused after that
+    shared_nodes: HashMap<(usize, NodeId), Arc<ListNode>>,
     selected_index: usize,
+    // Where each tab was left, restored when switching back to it
+    tab_locations: HashMap<usize, (Vec<(NodeId, usize)>, usize)>,
     
     // Multi-selection
     multi_select: bool,
-    selected_commands: Vec<Arc<ListNode>>,
+    // In the order the commands were picked, which is the order "Execute All" queues them in
+    selected_commands: Vec<ListEntry>,
+    show_selection: bool,
     
     // UI state
     search_text: String,
-    filtered_items: Vec<ListEntry>,
-    show_sidebar: bool,
+    search_scope: SearchScope,
+    // Only show commands carrying this task_list tag
+    tag_filter: Option<String>,
+    task_tags: Vec<String>,
+    // Indices into current_items of the entries matching the search and tag filter
+    filtered: Vec<usize>,
+    // Width of the grid and the number of columns fitting it, see grid_columns
+    grid_layout: (f32, usize),
+    show_settings: bool,
+    // New global environment variable being typed in the settings
+    env_draft: (String, String),
+    // Name of a new environment profile, and a new variable of the active one, being typed in
+    profile_name_draft: String,
+    // Redaction pattern being typed in the settings
+    redact_pattern_draft: String,
+    // Files of the scripts directory that didn't make it into the "My Scripts" tab, and why
+    script_problems: Vec<String>,
+    profile_env_draft: (String, String),
+    show_help: bool,
+    palette: Option<palette::Palette>,
+    // The Ctrl+T search over the names of the visible tabs
+    tab_switcher: Option<palette::Palette>,
+    focus_search: bool,
+    // Time of the last search edit that has not been applied yet, see SEARCH_DEBOUNCE
+    search_edited: Option<Instant>,
+    // Set when the selection moved by keyboard, so the selected card gets scrolled into view
+    scroll_to_selected: bool,
     
     // Command execution
-    command_output: String,
+    command_output: OutputText,
+    // Built lazily from command_output, so stderr can be colored without re-layouting every frame
+    command_output_job: Option<egui::text::LayoutJob>,
+    // Same as command_output, minus the per-line timestamps
+    command_output_plain: String,
+    // Whether the verify command of a single command run passed, for the badge above its output
+    command_output_verified: Option<bool>,
+    // Per-command sections of the last output when it came from a batch, shown instead of the
+    // concatenated command_output
+    command_output_batch: Option<BatchOutput>,
+    // Duration and start/end time of the shown output, for the window header
+    command_output_timing: String,
     show_command_output: bool,
+    // Keep the output scrolled to the newest lines; scrolling up pauses it until back at the bottom
+    auto_scroll_output: bool,
+    // Show runs of identical lines once with a count. Only the display changes, copying gives the
+    // output as printed
+    collapse_repeats: bool,
     executing_command: bool,
-    command_tx: Option<mpsc::Sender<(String, Arc<ListNode>)>>,
-    command_rx: Option<mpsc::Receiver<CommandResult>>,
+    // Number of unfinished commands while asking whether to close the window anyway
+    confirm_close: Option<usize>,
+    // Set once the user chose to close, so the next close request goes through
+    close_confirmed: bool,
+    pending_run: Option<PendingRun>,
+    preview: Option<ScriptPreview>,
+    highlight_preview: bool,
+    command_tx: Option<mpsc::Sender<QueuedBatch>>,
+    command_rx: Option<mpsc::Receiver<CommandEvent>>,
+    // Joined when the app is dropped, see the Drop impl
+    worker: Option<thread::JoinHandle<()>>,
+    // Every job queued since the panel was last cleared, in the order they run
+    queue: Vec<QueueItem>,
+    next_job_id: u64,
+    show_queue: bool,
+    schedule_draft: Option<ScheduleDraft>,
+    scheduled: Vec<ScheduledJob>,
+    show_scheduled: bool,
+    // Results of the current batch, shown together once the last command is done
+    pending_results: usize,
+    batch_results: Vec<(String, CommandResult)>,
+    batch_skipped: Vec<String>,
+    // "Stop" was pressed, so the summary reports the run as stopped rather than finished
+    batch_stopped: bool,
+    
+    // Execution history and the statistics derived from it
+    history: Vec<HistoryEntry>,
+    // The user's notes on commands, by command path
+    notes: BTreeMap<String, String>,
+    // Latest history entry of each command path, for the cards
+    last_runs: HashMap<String, HistoryEntry>,
+    // Paths of the last MAX_RECENT distinct commands that ran, newest first. Rebuilt from the
+    // history, so it persists with it
+    recent: Vec<String>,
+    // Path and node of the command that finished last, offered for a re-run in the status bar
+    last_executed_node: Option<(String, Arc<ListNode>)>,
+    show_stats: bool,
+    confirm_reset_stats: bool,
+    // Report of the last self-test while its window is open
+    self_test: Option<Vec<selftest::Check>>,
     
     // Status
     loading: bool,
+    // Why the tab data could not be loaded, shown in place of the grid until a retry succeeds
+    load_error: Option<String>,
     error_message: String,
     status_message: String,
+    // Colors the status bar after a run until STATUS_OUTCOME_DURATION passed or the user navigates
+    status_outcome: Option<(RunOutcome, Instant)>,
+    // When the output of a successful run was left closed, see AppConfig::auto_close_output
+    output_toast: Option<Instant>,
+    // Time of the last keyboard or pointer input, see AppConfig::idle_reset_mins
+    last_input: Instant,
+    // Notices in the corner, see AppConfig::show_toasts
+    toasts: toast::Toasts,
+    // Every result of the session, oldest first and at most AppConfig::output_dock_max of them.
+    // Kept while the dock is hidden, so it shows them once opened. The number identifies the
+    // entry's collapsing state
+    output_dock: VecDeque<(u64, OutputSection)>,
+    output_dock_next: u64,
 }
 
 impl LinutilApp {
-    fn new() -> Self {
+    fn new(forced_safe_mode: bool) -> Self {
+        let config = AppConfig::load();
+        i18n::init(&config.language);
         let mut app = Self {
-            tabs: get_tabs(false), // false = don't validate, show all commands
+            // Unless validation is turned on, show all commands regardless of compatibility
+            // Loaded on the second frame, so the loading screen gets a chance to show
+            tabs: TabList::default(),
             current_tab_index: 0,
-            theme: ModernTheme::new(),
+            theme: ModernTheme::from_config(&config),
+            keymap: shortcuts::Keymap::new(config.keymap_preset, &config.keybindings),
+            forced_safe_mode,
+            package_managers: backends::probe(),
+            missing_backends: HashMap::new(),
+            config,
             visit_stack: Vec::new(),
+            breadcrumb: "Loading...".to_string(),
             current_items: Vec::new(),
+            folder_len: 0,
+            shared_nodes: HashMap::new(),
             selected_index: 0,
+            tab_locations: HashMap::new(),
             multi_select: false,
             selected_commands: Vec::new(),
+            show_selection: false,
             search_text: String::new(),
-            filtered_items: Vec::new(),
-            show_sidebar: true,
-            command_output: String::new(),
+            search_scope: SearchScope::Folder,
+            tag_filter: None,
+            task_tags: Vec::new(),
+            filtered: Vec::new(),
+            grid_layout: (0.0, 1),
+            show_settings: false,
+            env_draft: Default::default(),
+            profile_name_draft: String::new(),
+            redact_pattern_draft: String::new(),
+            script_problems: Vec::new(),
+            profile_env_draft: Default::default(),
+            show_help: false,
+            palette: None,
+            tab_switcher: None,
+            focus_search: false,
+            search_edited: None,
+            scroll_to_selected: false,
+            command_output: OutputText::default(),
+            command_output_job: None,
+            command_output_plain: String::new(),
+            command_output_verified: None,
+            command_output_batch: None,
+            command_output_timing: String::new(),
             show_command_output: false,
+            auto_scroll_output: true,
+            collapse_repeats: false,
             executing_command: false,
+            confirm_close: None,
+            close_confirmed: false,
+            pending_run: None,
+            preview: None,
+            highlight_preview: true,
             command_tx: None,
+            worker: None,
             command_rx: None,
-            loading: false,
+            queue: Vec::new(),
+            next_job_id: 0,
+            show_queue: false,
+            schedule_draft: None,
+            scheduled: Vec::new(),
+            show_scheduled: false,
+            pending_results: 0,
+            batch_results: Vec::new(),
+            batch_skipped: Vec::new(),
+            batch_stopped: false,
+            history: Vec::new(),
+            notes: notes::load(),
+            last_runs: HashMap::new(),
+            recent: Vec::new(),
+            last_executed_node: None,
+            show_stats: false,
+            self_test: None,
+            confirm_reset_stats: false,
+            loading: true,
+            load_error: None,
             error_message: String::new(),
-            status_message: "Ready".to_string(),
+            status_message: tr("Ready"),
+            status_outcome: None,
+            output_toast: None,
+            last_input: Instant::now(),
+            toasts: toast::Toasts::default(),
+            output_dock: VecDeque::new(),
+            output_dock_next: 0,
         };
 
+        app.set_history(history::load());
+        if !app.keymap.warnings.is_empty() {
+            for warning in &app.keymap.warnings {
+                eprintln!("Keybindings: {}", warning);
+            }
+            app.status_message = tr_fmt("{0} problems with the keybindings, see the settings", &[&app.keymap.warnings.len()]);
+        }
+
         // Set up command execution channel
         let (cmd_tx, cmd_rx) = mpsc::channel();
         let (result_tx, result_rx) = mpsc::channel();
@@ -183,29 +738,98 @@ impl LinutilApp {
         app.command_tx = Some(cmd_tx);
         app.command_rx = Some(result_rx);
 
-        // Spawn command execution thread
-        thread::spawn(move || {
-            while let Ok((_tab_name, node)) = cmd_rx.recv() {
-                let result = execute_command_node(&node);
-                let _ = result_tx.send(result);
+        OWN_PROCESS_GROUP.store(true, Ordering::Relaxed);
+        app.worker = Some(spawn_worker(cmd_rx, result_tx));
+
+        app
+    }
+
+    // Loads the tab data from scratch. On failure the previous tabs are kept and the error is shown
+    // instead of the grid until a retry succeeds
+    fn load_tabs(&mut self) -> bool {
+        self.loading = false;
+        match load_all_tabs(&self.config) {
+            Ok((tabs, script_problems)) => {
+                self.load_error = None;
+                for problem in &script_problems {
+                    eprintln!("My Scripts: {}", problem);
+                }
+                self.script_problems = script_problems;
+                // Script paths point into the extraction directory of the old tabs, which is gone
+                // once they are dropped
+                self.selected_commands.clear();
+                self.multi_select = false;
+                self.preview = None;
+                self.pending_run = None;
+                self.last_executed_node = None;
+                self.schedule_draft = None;
+                self.replace_tabs(tabs);
+                self.reschedule_after_reload();
+                self.missing_backends = self.tabs.iter()
+                    .flat_map(|tab| {
+                        tab.tree.root().descendants()
+                            .filter(|node| !node.value().is_directory())
+                            .filter_map(|node| {
+                                let manager = backends::required_by(node.value())?;
+                                (!self.package_managers.contains(&manager)).then(|| (node_path(&tab.name, node), manager))
+                            })
+                    })
+                    .collect();
+                self.status_message = tr_fmt("Loaded {0} categories with {1} total utilities", &[
+                    &self.tabs.len(),
+                    &self.tabs.iter().map(|t| t.tree.root().descendants().count() - 1).sum::<usize>(),
+                ]);
+                if !self.script_problems.is_empty() {
+                    self.status_message = tr_fmt("{0} of your scripts were skipped, see the settings", &[&self.script_problems.len()]);
+                }
+                true
             }
-        });
+            Err(e) => {
+                self.load_error = Some(e);
+                self.breadcrumb = self.get_breadcrumb();
+                self.status_message = tr("Failed to load utilities");
+                false
+            }
+        }
+    }
 
-        // Initialize navigation
-        if !app.tabs.is_empty() {
-            let root_id = app.tabs[0].tree.root().id();
-            app.visit_stack.push((root_id, 0));
-            app.update_items();
-            app.status_message = format!("Loaded {} categories with {} total utilities", 
-                                       app.tabs.len(), 
-                                       app.tabs.iter().map(|t| t.tree.root().descendants().count() - 1).sum::<usize>());
+    // Scheduled jobs hold nodes of the old tabs, whose scripts are deleted along with them. Each one
+    // is pointed at the command of the same path in the new tabs, or cancelled if there is none
+    fn reschedule_after_reload(&mut self) {
+        let mut cancelled = Vec::new();
+        for ScheduledJob { job, due } in std::mem::take(&mut self.scheduled) {
+            let node = self.tabs.iter().find_map(|tab| {
+                tab.tree.root().descendants()
+                    .find(|node| !node.value().is_directory() && node_path(&tab.name, *node) == job.path)
+                    .map(|node| Arc::new((**node.value()).clone()))
+            });
+            match node {
+                Some(node) => self.scheduled.push(ScheduledJob { job: Job { node, ..job }, due }),
+                None => cancelled.push(job.path),
+            }
         }
+        if !cancelled.is_empty() {
+            self.toasts.push(Severity::Warning, tr_fmt("Cancelled the scheduled run of {0}, which is gone after the reload", &[&cancelled.join(", ")]));
+        }
+    }
 
-        app
+    fn reload_tabs(&mut self) {
+        // Running scripts are read from the extraction directory, which a reload deletes
+        if self.executing_command {
+            self.status_message = tr("Cannot reload while commands are running");
+            return;
+        }
+        if self.load_tabs() {
+            self.status_message = tr_fmt("Reloaded {0} categories", &[&self.tabs.len()]);
+        }
     }
 
     fn update_items(&mut self) {
+        self.breadcrumb = self.get_breadcrumb();
         if self.tabs.is_empty() {
+            self.current_items.clear();
+            self.filtered.clear();
+            self.task_tags.clear();
             return;
         }
 
@@ -219,52 +843,148 @@ impl LinutilApp {
         self.current_items.clear();
         for child in current_node.children() {
             let child_value = child.value();
-            let has_children = child.has_children();
-            
+            let node = self.shared_nodes
+                .entry((self.current_tab_index, child.id()))
+                .or_insert_with(|| Arc::new((**child_value).clone()))
+                .clone();
+
             self.current_items.push(ListEntry {
-                node: Arc::new((**child_value).clone()),
+                node,
                 id: child.id(),
-                has_children,
+                tab: self.current_tab_index,
+                path: format!("{} › {}", self.breadcrumb, child_value.name),
+                is_directory: child_value.is_directory(),
             });
         }
 
+        self.folder_len = self.current_items.len();
+        for (tab_index, tab) in self.tabs.iter().enumerate() {
+            let in_scope = match self.search_scope {
+                SearchScope::Folder => false,
+                SearchScope::Tab => tab_index == self.current_tab_index,
+                SearchScope::AllTabs => true,
+            };
+            if !in_scope {
+                continue;
+            }
+            // Skip the root, and the children of the current folder which are listed already
+            for node in tab.tree.root().descendants().skip(1) {
+                if tab_index == self.current_tab_index && node.parent().is_some_and(|parent| parent.id() == current_node_id) {
+                    continue;
+                }
+                let shared = self.shared_nodes
+                    .entry((tab_index, node.id()))
+                    .or_insert_with(|| Arc::new((**node.value()).clone()))
+                    .clone();
+                self.current_items.push(ListEntry {
+                    node: shared,
+                    id: node.id(),
+                    tab: tab_index,
+                    path: node_path(&tab.name, node),
+                    is_directory: node.value().is_directory(),
+                });
+            }
+        }
+
+        // Collect the distinct task_list tags of the whole tab for the tag filter
+        let mut task_tags: Vec<String> = Vec::new();
+        for node in current_tab.tree.root().descendants() {
+            for tag in node.value().task_list.split_whitespace() {
+                if !task_tags.iter().any(|t| t == tag) {
+                    task_tags.push(tag.to_string());
+                }
+            }
+        }
+        task_tags.sort();
+        if self.tag_filter.as_ref().is_some_and(|tag| !task_tags.contains(tag)) {
+            self.tag_filter = None;
+        }
+        self.task_tags = task_tags;
+
         // Apply search filter
         self.apply_search_filter();
         
         // Ensure selected index is valid
-        if self.selected_index >= self.filtered_items.len() && !self.filtered_items.is_empty() {
+        if self.selected_index >= self.filtered.len() && !self.filtered.is_empty() {
             self.selected_index = 0;
         }
     }
 
+    // Only recomputes the indices, the entries themselves are built once per navigation
     fn apply_search_filter(&mut self) {
-        if self.search_text.is_empty() {
-            self.filtered_items = self.current_items.clone();
+        self.search_edited = None;
+        // Entries outside the current folder only show up as search results
+        let candidates = if self.search_text.is_empty() {
+            &self.current_items[..self.folder_len]
+        } else {
+            &self.current_items[..]
+        };
+        if self.search_text.is_empty() && self.tag_filter.is_none() {
+            self.filtered = (0..candidates.len()).collect();
         } else {
             let search_lower = self.search_text.to_lowercase();
-            self.filtered_items = self.current_items
+            let has_tag = |node: &ListNode, tag: &str| node.task_list.split_whitespace().any(|t| t == tag);
+
+            self.filtered = candidates
                 .iter()
-                .filter(|entry| {
+                .enumerate()
+                .filter(|(_, entry)| {
                     entry.node.name.to_lowercase().contains(&search_lower) ||
-                    entry.node.description.to_lowercase().contains(&search_lower)
+                    entry.node.description.to_lowercase().contains(&search_lower) ||
+                    entry.node.task_list.to_lowercase().contains(&search_lower) ||
+                    self.notes.get(&entry.path).is_some_and(|note| note.to_lowercase().contains(&search_lower))
+                })
+                .filter(|(_, entry)| {
+                    // Directories stay visible when something inside them carries the tag
+                    self.tag_filter.as_deref().is_none_or(|tag| {
+                        has_tag(&entry.node, tag) || (entry.is_directory && self.tabs[entry.tab].tree.get(entry.id)
+                            .is_some_and(|node| node.descendants().any(|child| has_tag(child.value(), tag))))
+                    })
                 })
-                .cloned()
+                .map(|(i, _)| i)
                 .collect();
         }
+
+        if self.search_scope == SearchScope::AllTabs && !self.search_text.is_empty() {
+            // Grouped by tab in sidebar order, best match first within each tab
+            let order = self.sidebar_order();
+            let tab_rank = |tab: usize| order.iter().position(|&t| t == tab).unwrap_or(usize::MAX);
+            let score = |node: &ListNode| {
+                palette::fuzzy_score(&self.search_text, &node.name)
+                    .or_else(|| palette::fuzzy_score(&self.search_text, &node.description))
+                    .unwrap_or(i32::MIN)
+            };
+            self.filtered.sort_by_cached_key(|&i| {
+                let entry = &self.current_items[i];
+                (tab_rank(entry.tab), std::cmp::Reverse(score(&entry.node)))
+            });
+        }
+    }
+
+    fn filtered_entry(&self, index: usize) -> Option<&ListEntry> {
+        self.filtered.get(index).map(|&i| &self.current_items[i])
     }
 
     fn enter_directory(&mut self) {
-        let selected_info = self.filtered_items.get(self.selected_index)
-            .map(|entry| (entry.id, entry.has_children, entry.node.name.clone()));
+        let selected_info = self.filtered.get(self.selected_index)
+            .map(|&item| {
+                let entry = &self.current_items[item];
+                (item, entry.tab, entry.id, entry.is_directory, entry.node.name.clone())
+            });
         
-        if let Some((entry_id, has_children, node_name)) = selected_info {
-            if has_children {
+        if let Some((item, tab_index, entry_id, is_directory, node_name)) = selected_info {
+            // A search result from outside the current folder: jump to where it lives
+            if is_directory && item >= self.folder_len {
+                self.navigate_to(tab_index, entry_id);
+                self.status_message = tr_fmt("Navigated to {0}", &[&node_name]);
+            } else if is_directory {
                 // Enter the directory
                 self.visit_stack.push((entry_id, self.selected_index));
+                self.status_outcome = None;
                 self.selected_index = 0;
                 self.search_text.clear();
                 self.update_items();
-                self.status_message = format!("Navigated to {}", node_name);
+                self.status_message = tr_fmt("Navigated to {0}", &[&node_name]);
             }
         }
     }
@@ -272,21 +992,33 @@ impl LinutilApp {
     fn go_back(&mut self) {
         if self.visit_stack.len() > 1 {
             if let Some((_, previous_selection)) = self.visit_stack.pop() {
+                self.status_outcome = None;
                 self.selected_index = previous_selection;
                 self.search_text.clear();
                 self.update_items();
-                self.status_message = "Navigated back".to_string();
+                self.status_message = tr("Navigated back");
             }
         }
     }
 
+    // Number of card columns that fit the width, only recomputed when the width changes
+    fn grid_columns(&mut self, available_width: f32) -> usize {
+        if self.grid_layout.0 != available_width {
+            let card_width = 350.0;
+            let spacing = 16.0;
+            let cols = ((available_width + spacing) / (card_width + spacing)).floor() as usize;
+            self.grid_layout = (available_width, cols.max(1));
+        }
+        self.grid_layout.1
+    }
+
     fn at_root(&self) -> bool {
         self.visit_stack.len() <= 1
     }
 
     fn get_breadcrumb(&self) -> String {
         if self.tabs.is_empty() {
-            return "Loading...".to_string();
+            return if self.loading { "Loading...".to_string() } else { String::new() };
         }
         
         let current_tab = &self.tabs[self.current_tab_index];
@@ -302,476 +1034,3591 @@ impl LinutilApp {
     }
 
     fn execute_selected_command(&mut self) {
-        if let Some(selected_entry) = self.filtered_items.get(self.selected_index) {
-            if !selected_entry.has_children {
+        if let Some(selected_entry) = self.filtered_entry(self.selected_index) {
+            if !selected_entry.is_directory {
                 // It's a command, execute it
-                if let Some(tx) = &self.command_tx {
-                    self.executing_command = true;
-                    let tab_name = self.tabs[self.current_tab_index].name.clone();
-                    let _ = tx.send((tab_name, selected_entry.node.clone()));
-                    self.status_message = format!("Executing: {}", selected_entry.node.name);
-                }
+                let job = self.job(selected_entry.path.clone(), &selected_entry.node);
+                self.request_run(vec![job], 1, false);
             }
         }
     }
 
-    fn toggle_multi_select(&mut self) {
-        if let Some(selected_entry) = self.filtered_items.get(self.selected_index) {
-            if !selected_entry.has_children && selected_entry.node.multi_select {
-                if let Some(pos) = self.selected_commands.iter().position(|x| Arc::ptr_eq(x, &selected_entry.node)) {
-                    self.selected_commands.remove(pos);
-                    self.status_message = format!("Removed {} from selection", selected_entry.node.name);
-                } else {
-                    self.selected_commands.push(selected_entry.node.clone());
-                    self.status_message = format!("Added {} to selection", selected_entry.node.name);
+    // A job for a command, with the environment and working directory configured for it
+    fn job(&self, path: String, node: &Arc<ListNode>) -> Job {
+        Job {
+            id: 0,
+            env: self.config.env_for(&path),
+            cwd: self.config.command_dir(),
+            path,
+            node: node.clone(),
+        }
+    }
+
+    // Every command directly in a directory, or anywhere below it when recursive, as one batch
+    fn run_folder(&mut self, tab_index: usize, directory: NodeId, recursive: bool) {
+        let tab = &self.tabs[tab_index];
+        let Some(folder) = tab.tree.get(directory) else {
+            return;
+        };
+        let nodes: Vec<_> = if recursive {
+            folder.descendants().skip(1).collect()
+        } else {
+            folder.children().collect()
+        };
+        let commands: Vec<(NodeId, String)> = nodes.into_iter()
+            .filter(|node| !node.value().is_directory())
+            .map(|node| (node.id(), node_path(&tab.name, node)))
+            .collect();
+        if commands.is_empty() {
+            self.status_message = tr_fmt("No commands in {0}", &[&folder.value().name]);
+            return;
+        }
+
+        let jobs = commands.into_iter()
+            .map(|(id, path)| {
+                let node = self.shared_nodes
+                    .entry((tab_index, id))
+                    .or_insert_with(|| Arc::new((**self.tabs[tab_index].tree.get(id).unwrap().value()).clone()))
+                    .clone();
+                Job { id: 0, env: self.config.env_for(&path), cwd: self.config.command_dir(), path, node }
+            })
+            .collect();
+        self.request_run(jobs, self.config.max_parallel, false);
+    }
+
+    fn safe_mode(&self) -> bool {
+        self.forced_safe_mode || self.config.safe_mode
+    }
+
+    // Runs the jobs right away when confirmation is turned off, otherwise opens the confirmation
+    // dialog for them
+    fn request_run(&mut self, jobs: Vec<Job>, max_parallel: usize, from_selection: bool) {
+        if self.safe_mode() {
+            self.status_message = tr(SAFE_MODE_HINT);
+            return;
+        }
+        let missing: Vec<Vec<String>> = jobs.iter()
+            .map(|job| job.node.missing_dependencies().into_iter().map(String::from).collect())
+            .collect();
+        // Missing dependencies and dangerous commands are always worth a look, even with
+        // confirmation turned off
+        let dangerous = jobs.iter().any(|job| job.node.danger);
+        let skip = self.config.skip_confirmation || jobs.iter().all(|job| self.config.skip_confirm_for.contains(&job.path));
+        let (free_space, low_space) = self.disk_space(&jobs);
+        if skip && !dangerous && !low_space && missing.iter().all(Vec::is_empty) {
+            self.start_run(CommandBatch { jobs, max_parallel }, from_selection);
+            return;
+        }
+
+        let exec = self.config.exec_options();
+        let resolved = jobs.iter().map(|job| resolve_command(&job.node.command, &job.exec_options(&exec))).collect();
+        let typed_names = vec![String::new(); jobs.len()];
+        let dont_ask = vec![false; jobs.len()];
+        let dry_runs = jobs.iter().map(|job| self.start_dry_runs(&job.node)).collect();
+        self.pending_run = Some(PendingRun {
+            batch: CommandBatch { jobs, max_parallel },
+            resolved,
+            missing,
+            from_selection,
+            typed_names,
+            dont_ask,
+            free_space,
+            dry_runs,
+        });
+    }
+
+    // Free space of / when one of the jobs installs something and the check is on, and whether it
+    // is below the minimum
+    fn disk_space(&self, jobs: &[Job]) -> (Option<u64>, bool) {
+        let installs = self.config.disk_space_check && jobs.iter().any(|job| is_install(&job.node));
+        let free_space = if installs { free_disk_space(Path::new("/")) } else { None };
+        let low_space = free_space.is_some_and(|free| free < self.config.min_free_space_mb * 1024 * 1024);
+        (free_space, low_space)
+    }
+
+    fn confirm_pending_run(&mut self) {
+        if !self.pending_run.as_ref().is_some_and(PendingRun::confirmed) {
+            return;
+        }
+        if let Some(pending) = self.pending_run.take() {
+            let exempted: Vec<String> = pending.batch.jobs.iter()
+                .zip(&pending.dont_ask)
+                .filter(|(_, dont_ask)| **dont_ask)
+                .map(|(job, _)| job.path.clone())
+                .collect();
+            if !exempted.is_empty() {
+                self.config.skip_confirm_for.extend(exempted);
+                if let Err(e) = self.config.save() {
+                    self.error_message = format!("Failed to save settings: {}", e);
                 }
             }
+            self.start_run(pending.batch, pending.from_selection);
         }
     }
 
-    fn check_command_result(&mut self) {
-        if let Some(rx) = &self.command_rx {
-            if let Ok(result) = rx.try_recv() {
-                self.executing_command = false;
-                self.command_output = if result.success {
-                    format!("✅ Command executed successfully!\n\n{}", result.output)
-                } else {
-                    format!("❌ Command failed!\n\n{}\n\nError: {}", 
-                           result.output, result.error.unwrap_or_default())
-                };
-                self.show_command_output = true;
-                self.status_message = if result.success { 
-                    "Command completed successfully".to_string() 
-                } else { 
-                    "Command failed".to_string() 
-                };
-            }
+    fn start_run(&mut self, batch: CommandBatch, from_selection: bool) {
+        self.status_message = match batch.jobs.as_slice() {
+            [job] => tr_fmt("Executing: {0}", &[&job.node.name]),
+            jobs => tr_fmt("Executing {0} commands", &[&jobs.len()]),
+        };
+        self.send_batch(batch.jobs, batch.max_parallel);
+        if from_selection {
+            self.selected_commands.clear();
+            self.multi_select = false;
         }
     }
 
-    fn switch_tab(&mut self, tab_index: usize) {
-        if tab_index < self.tabs.len() && tab_index != self.current_tab_index {
-            self.current_tab_index = tab_index;
-            // Reset navigation to root of new tab
-            let root_id = self.tabs[tab_index].tree.root().id();
-            self.visit_stack = vec![(root_id, 0)];
-            self.selected_index = 0;
-            self.search_text.clear();
-            self.update_items();
-            self.status_message = format!("Switched to {}", self.tabs[tab_index].name);
+    fn send_batch(&mut self, jobs: Vec<Job>, max_parallel: usize) {
+        // Scheduled jobs come straight here, so this is the last line of defense
+        if self.safe_mode() {
+            self.status_message = tr(SAFE_MODE_HINT);
+            return;
         }
+        let Some(tx) = &self.command_tx else {
+            return;
+        };
+        let started = match jobs.as_slice() {
+            [job] => tr_fmt("Started {0}", &[&job.node.name]),
+            jobs => tr_fmt("Started {0} commands", &[&jobs.len()]),
+        };
+        self.toasts.push(Severity::Info, started);
+        if jobs.len() > 1 {
+            self.show_queue = true;
+        }
+        self.executing_command = true;
+        self.pending_results += jobs.len();
+        let (queue, items) = queue_jobs(jobs, &mut self.next_job_id);
+        self.queue.extend(items);
+        let _ = tx.send(QueuedBatch {
+            queue,
+            max_parallel,
+            stop_on_failure: self.config.stop_on_failure,
+            exec: self.config.exec_options(),
+            exec_log: self.config.exec_log_path(),
+            retry: self.config.retry_policy(),
+        });
     }
 
-    fn render_modern_button(&self, ui: &mut egui::Ui, text: &str, icon: &str, color: egui::Color32) -> egui::Response {
-        let button_height = 32.0;
-        let (rect, response) = ui.allocate_exact_size(
-            egui::vec2(ui.available_width(), button_height),
-            egui::Sense::click()
-        );
+    // Drops a job the worker has not started yet
+    fn remove_from_queue(&mut self, index: usize) {
+        if !self.queue[index].remove_pending() {
+            return;
+        }
+        let item = self.queue.remove(index);
+        self.pending_results = self.pending_results.saturating_sub(1);
+        self.status_message = tr_fmt("Removed {0} from the queue", &[&item.path]);
+        // With nothing left to report, the results check would never finish the run
+        if self.pending_results == 0 && self.batch_results.is_empty() {
+            self.executing_command = false;
+        }
+    }
 
-        if ui.is_rect_visible(rect) {
-            let visuals = ui.style().interact(&response);
-            let bg_color = if response.hovered() {
-                color.gamma_multiply(1.2)
+    // Back to the root of the first tab with nothing selected, searched or open
+    fn reset_after_idle(&mut self) {
+        self.show_settings = false;
+        self.show_help = false;
+        self.show_selection = false;
+        self.show_queue = false;
+        self.show_scheduled = false;
+        self.show_command_output = false;
+        self.show_stats = false;
+        self.confirm_reset_stats = false;
+        self.confirm_close = None;
+        self.palette = None;
+        self.tab_switcher = None;
+        self.preview = None;
+        self.pending_run = None;
+        self.schedule_draft = None;
+        self.self_test = None;
+        self.output_toast = None;
+        self.toasts.clear();
+        self.output_dock.clear();
+        self.error_message.clear();
+        self.selected_commands.clear();
+        self.multi_select = false;
+        self.search_scope = SearchScope::Folder;
+        if let Some(&first) = self.visible_tabs().first() {
+            let root = self.tabs[first].tree.root().id();
+            self.navigate_to(first, root);
+            // Other tabs open at their root too
+            self.tab_locations.clear();
+        }
+        self.status_outcome = None;
+        self.status_message = tr("Ready");
+    }
+
+    // Dry runs of the package operations a command spells out, for the package managers this
+    // system has. Operations on variables, as most scripts use, are not known before they run
+    fn start_dry_runs(&self, node: &ListNode) -> Vec<DryRun> {
+        let Some(body) = backends::command_body(node) else {
+            return Vec::new();
+        };
+        backends::package_ops(&body)
+            .into_iter()
+            .filter(|op| self.package_managers.contains(&op.manager))
+            .filter_map(|op| op.dry_run())
+            .map(DryRun::start)
+            .collect()
+    }
+
+    // Kills the running commands and has the worker skip every job it has not started, including
+    // those of batches still waiting behind the current one. The skipped jobs are reported back
+    // like those of a stop-on-failure batch, so the summary shows what ran and what didn't.
+    fn stop_run(&mut self) {
+        if !self.executing_command {
+            return;
+        }
+        STOPPING.store(true, Ordering::Relaxed);
+        self.batch_stopped = true;
+        // The processes stay in the map: the worker reaps them and reports them as failed
+        for child in RUNNING_CHILDREN.lock().unwrap().values_mut() {
+            send_kill(child);
+        }
+        self.status_message = tr("Stopping...");
+    }
+
+    // Jobs are queued in selection order and the worker starts them front to back, so a serial
+    // batch runs exactly in the order of the selection window
+    fn execute_all_selected(&mut self) {
+        let jobs = self.selected_commands
+            .iter()
+            .map(|entry| self.job(entry.path.clone(), &entry.node))
+            .collect();
+        self.request_run(jobs, self.config.max_parallel, true);
+    }
+
+    // Starts the scheduled jobs that are due and returns how long until the next one
+    fn run_due_schedules(&mut self) -> Option<Duration> {
+        let now = SystemTime::now();
+        let (due, waiting) = std::mem::take(&mut self.scheduled)
+            .into_iter()
+            .partition(|scheduled| scheduled.due <= now);
+        self.scheduled = waiting;
+        for ScheduledJob { job, .. } in due {
+            // Scheduling was the confirmation, with the typed name for dangerous commands. What may
+            // have changed since then is checked again, and as nobody may be there to ask, a job
+            // that would need confirming now is skipped
+            let missing = job.node.missing_dependencies();
+            let skipped = if !missing.is_empty() {
+                Some(tr_fmt("not installed: {0}", &[&missing.join(", ")]))
+            } else if self.disk_space(std::slice::from_ref(&job)).1 {
+                Some(tr_fmt("less than {0} MB free on /", &[&self.config.min_free_space_mb]))
             } else {
-                color
+                None
             };
+            match skipped {
+                Some(reason) => {
+                    self.status_message = tr_fmt("Skipped the scheduled run of {0}: {1}", &[&job.node.name, &reason]);
+                    self.toasts.push(Severity::Error, self.status_message.clone());
+                }
+                None => self.start_run(CommandBatch { jobs: vec![job], max_parallel: 1 }, false),
+            }
+        }
+        self.scheduled.iter()
+            .map(|scheduled| scheduled.due.duration_since(now).unwrap_or_default())
+            .min()
+    }
 
-            ui.painter().rect_filled(rect, visuals.rounding, bg_color);
-            
-            let text_color = if response.hovered() {
-                egui::Color32::WHITE
+    fn toggle_multi_select(&mut self) {
+        let selected_entry = self.filtered_entry(self.selected_index)
+            .filter(|entry| !entry.is_directory && entry.node.multi_select)
+            .cloned();
+        if let Some(entry) = selected_entry {
+            if let Some(pos) = self.selected_commands.iter().position(|selected| selected.is_same(&entry)) {
+                self.selected_commands.remove(pos);
+                self.status_message = tr_fmt("Removed {0} from selection", &[&entry.node.name]);
             } else {
-                self.theme.on_surface
-            };
+                self.status_message = tr_fmt("Added {0} to selection", &[&entry.node.name]);
+                self.selected_commands.push(entry);
+            }
+        }
+    }
 
-            ui.painter().text(
-                rect.left_center() + egui::vec2(12.0, 0.0),
-                egui::Align2::LEFT_CENTER,
-                format!("{} {}", icon, text),
-                egui::FontId::proportional(14.0),
-                text_color,
-            );
+    // Same guard as toggle_multi_select: only commands that allow multi-selection are added
+    fn select_all_shown(&mut self) {
+        let mut added = 0;
+        for &i in &self.filtered {
+            let entry = &self.current_items[i];
+            if entry.is_directory || !entry.node.multi_select {
+                continue;
+            }
+            if !self.selected_commands.iter().any(|selected| selected.is_same(entry)) {
+                self.selected_commands.push(entry.clone());
+                added += 1;
+            }
         }
+        if added > 0 {
+            self.multi_select = true;
+        }
+        self.status_message = tr_fmt("Added {0} commands to the selection", &[&added]);
+    }
 
-        response
+    fn set_history(&mut self, history: Vec<HistoryEntry>) {
+        // Entries are in the order they finished, so later runs replace earlier ones
+        self.last_runs = history.iter()
+            .map(|entry| (entry.path.clone(), entry.clone()))
+            .collect();
+        self.recent.clear();
+        for entry in &history {
+            self.remember_recent(&entry.path);
+        }
+        self.history = history;
     }
 
-    fn render_category_card(&self, ui: &mut egui::Ui, entry: &ListEntry, _index: usize) -> Option<String> {
-        let mut action = None;
-        
-        let is_multi_selected = self.selected_commands.iter().any(|cmd| Arc::ptr_eq(cmd, &entry.node));
-        
-        // Card styling
-        let card_color = if is_multi_selected {
-            self.theme.primary.gamma_multiply(0.3)
-        } else {
-            self.theme.surface
+    fn remember_recent(&mut self, path: &str) {
+        self.recent.retain(|recent| recent != path);
+        self.recent.insert(0, path.to_string());
+        self.recent.truncate(MAX_RECENT);
+    }
+
+    // Lists the commands of the tabs shown in the sidebar, in sidebar order
+    fn open_palette(&mut self) {
+        let paths = self.sidebar_order()
+            .into_iter()
+            .map(|i| &self.tabs[i])
+            .filter(|tab| !self.config.hidden_tabs.contains(&tab.name))
+            .flat_map(|tab| {
+                tab.tree.root().descendants()
+                    .filter(|node| !node.value().is_directory())
+                    .map(|node| node_path(&tab.name, node))
+            })
+            .collect();
+        self.tab_switcher = None;
+        self.palette = Some(palette::Palette::new(paths));
+    }
+
+    // Lists the tabs shown in the sidebar, in sidebar order
+    fn open_tab_switcher(&mut self) {
+        let names = self.visible_tabs()
+            .into_iter()
+            .map(|i| self.tabs[i].name.clone())
+            .collect();
+        self.palette = None;
+        self.tab_switcher = Some(palette::Palette::new(names));
+    }
+
+    // Sidebar order without the hidden tabs
+    fn visible_tabs(&self) -> Vec<usize> {
+        self.sidebar_order()
+            .into_iter()
+            .filter(|&i| !self.config.hidden_tabs.contains(&self.tabs[i].name))
+            .collect()
+    }
+
+    // Opens a directory of any tab, with a visit stack as if it had been entered from the root
+    fn navigate_to(&mut self, tab_index: usize, directory: NodeId) {
+        self.switch_tab(tab_index);
+        let Some(node) = self.tabs[tab_index].tree.get(directory) else {
+            return;
         };
+        let mut visit_stack: Vec<(NodeId, usize)> = std::iter::once(node)
+            .chain(node.ancestors())
+            .map(|ancestor| (ancestor.id(), 0))
+            .collect();
+        visit_stack.reverse();
+        self.visit_stack = visit_stack;
+        self.selected_index = 0;
+        self.search_text.clear();
+        self.tag_filter = None;
+        self.update_items();
+    }
 
-        let response = egui::Frame::none()
-            .fill(card_color)
-            .rounding(12.0)
-            .inner_margin(egui::Margin::same(16.0))
-            .stroke(egui::Stroke::new(1.0, self.theme.border))
-            .show(ui, |ui| {
-                ui.vertical(|ui| {
-                    // Header with icon and title
-                    ui.horizontal(|ui| {
-                        let icon = if entry.has_children { "📁" } else { "⚙️" };
-                        let status_icon = if is_multi_selected { " ✅" } else { "" };
+    // Navigates to the directory holding the command with the given path and selects it
+    fn reveal_command(&mut self, path: &str) -> Option<Arc<ListNode>> {
+        let (tab_index, node_id) = self.tabs.iter().enumerate().find_map(|(tab_index, tab)| {
+            tab.tree.root().descendants()
+                .find(|node| !node.value().is_directory() && node_path(&tab.name, *node) == path)
+                .map(|node| (tab_index, node.id()))
+        })?;
+
+        let parent_id = self.tabs[tab_index].tree.get(node_id)?.parent()?.id();
+        self.navigate_to(tab_index, parent_id);
+
+        let index = self.filtered.iter().position(|&i| self.current_items[i].id == node_id)?;
+        self.selected_index = index;
+        self.scroll_to_selected = true;
+        self.filtered_entry(index).map(|entry| entry.node.clone())
+    }
+
+    fn check_command_result(&mut self) {
+        let Some(rx) = &self.command_rx else {
+            return;
+        };
+        let events: Vec<_> = rx.try_iter().collect();
+        for event in events {
+            let (id, path, node, result) = match event {
+                CommandEvent::Started { id } => {
+                    if let Some(item) = self.queue.iter_mut().find(|item| item.id == id) {
+                        item.status = QueueStatus::Running;
+                    }
+                    continue;
+                }
+                CommandEvent::Retrying { id, attempt, delay } => {
+                    if let Some(item) = self.queue.iter().find(|item| item.id == id) {
+                        self.status_message = tr_fmt("{0} failed, attempt {1} in {2}", &[&item.path, &attempt, &format_duration(delay)]);
+                    }
+                    continue;
+                }
+                CommandEvent::Finished { id, path, node, result } => (id, path, node, *result),
+                CommandEvent::Skipped { id, node } => {
+                    self.pending_results = self.pending_results.saturating_sub(1);
+                    if let Some(item) = self.queue.iter_mut().find(|item| item.id == id) {
+                        item.status = QueueStatus::Skipped;
+                    }
+                    self.batch_skipped.push(node.name.clone());
+                    continue;
+                }
+            };
+            self.pending_results = self.pending_results.saturating_sub(1);
+            if let Some(item) = self.queue.iter_mut().find(|item| item.id == id) {
+                item.status = if result.success { QueueStatus::Succeeded } else { QueueStatus::Failed };
+            }
+
+            let entry = HistoryEntry::new(path, node.name.clone(), result.success, result.elapsed);
+            if let Err(e) = history::record(&entry) {
+                eprintln!("Failed to record command history: {}", e);
+            }
+            self.last_runs.insert(entry.path.clone(), entry.clone());
+            self.remember_recent(&entry.path);
+            self.last_executed_node = Some((entry.path.clone(), node.clone()));
+            self.history.push(entry);
+            self.batch_results.push((node.name.clone(), result));
+        }
+
+        // A stopped run may have nothing but skipped jobs
+        if self.pending_results > 0 || (self.batch_results.is_empty() && self.batch_skipped.is_empty()) {
+            return;
+        }
+        self.executing_command = false;
+        STOPPING.store(false, Ordering::Relaxed);
+        let stopped = std::mem::take(&mut self.batch_stopped);
+        let results = std::mem::take(&mut self.batch_results);
+        let skipped = std::mem::take(&mut self.batch_skipped);
+        let (ended, skipped_heading) = if stopped {
+            ("Batch stopped", "Skipped, the run was stopped")
+        } else {
+            ("Batch finished", "Skipped after a failure")
+        };
+        let failed = results.iter().filter(|(_, result)| !result.success).count();
+
+        // Parallel batches overlap, so the batch spans from the first start to the last finish
+        let started_at = results.iter().map(|(_, result)| result.started_at).min().unwrap_or_else(SystemTime::now);
+        let finished_at = results.iter().map(|(_, result)| result.started_at + result.elapsed).max().unwrap_or(started_at);
+        let elapsed = finished_at.duration_since(started_at).unwrap_or_default();
+        self.command_output_timing = format!("Completed in {} · {} – {}",
+                                             format_duration(elapsed),
+                                             format_clock(started_at),
+                                             format_clock(finished_at));
+        if let [(_, result)] = results.as_slice() {
+            self.command_output_timing.push_str(&format!(" · {}", exit_status_label(result)));
+        }
+
+        let streams = self.config.output_streams;
+        let format_all = |timestamps: bool| {
+            let mut output = OutputText::default();
+            match results.as_slice() {
+                [(_, result)] if skipped.is_empty() => format_result(&mut output, result, timestamps, streams),
+                results => {
+                    output.push(&format!("{}: {} succeeded, {} failed, {} skipped\n\n",
+                                         ended, results.len() - failed, failed, skipped.len()));
+                    for (name, result) in results {
+                        output.push(&format!("━━━ {} ({}, {}) ━━━\n", name, format_duration(result.elapsed), exit_status_label(result)));
+                        format_result(&mut output, result, timestamps, streams);
+                        output.push("\n\n");
+                    }
+                    if !skipped.is_empty() {
+                        output.push(&format!("━━━ {} ━━━\n{}\n", skipped_heading, skipped.join("\n")));
+                    }
+                }
+            }
+            output
+        };
+        self.command_output_plain = format_all(false).text;
+        self.command_output_verified = match results.as_slice() {
+            [(_, result)] if skipped.is_empty() => result.verification.as_ref().map(|verification| verification.passed),
+            _ => None,
+        };
+        self.command_output = format_all(self.config.timestamp_output);
+        self.command_output_job = None;
+        self.command_output_batch = (results.len() > 1 || !skipped.is_empty()).then(|| BatchOutput {
+            id: self.next_job_id,
+            summary: format!("{}: {} succeeded, {} failed, {} skipped",
+                             ended, results.len() - failed, failed, skipped.len()),
+            sections: results.iter().map(|(name, result)| {
+                let mut output = OutputText::default();
+                format_result(&mut output, result, self.config.timestamp_output, streams);
+                OutputSection {
+                    title: format!("{} {} ({}, {})", if result.success { "✔" } else { "✘" }, name,
+                                   format_duration(result.elapsed), exit_status_label(result)),
+                    success: result.success,
+                    verified: result.verification.as_ref().map(|verification| verification.passed),
+                    output,
+                    job: None,
+                }
+            }).collect(),
+            skipped: skipped.clone(),
+            skipped_heading,
+        });
+        for (name, result) in &results {
+            let mut output = OutputText::default();
+            format_result(&mut output, result, self.config.timestamp_output, streams);
+            self.output_dock.push_back((self.output_dock_next, OutputSection {
+                title: format!("{} {} · {} ({}, {})", if result.success { "✔" } else { "✘" }, name,
+                               format_clock(result.started_at), format_duration(result.elapsed), exit_status_label(result)),
+                success: result.success,
+                verified: result.verification.as_ref().map(|verification| verification.passed),
+                output,
+                job: None,
+            }));
+            self.output_dock_next += 1;
+        }
+        self.trim_output_dock();
+        // The dock shows the result already. Otherwise, with auto-close, only runs that need a
+        // look open the window
+        if self.config.output_dock {
+            self.output_toast = None;
+        } else if self.config.auto_close_output && failed == 0 && skipped.is_empty() {
+            self.output_toast = Some(Instant::now());
+        } else {
+            self.output_toast = None;
+            self.show_command_output = true;
+        }
+        let retried = results.iter().any(|(_, result)| !result.failed_attempts.is_empty());
+        let outcome = match (failed, skipped.len()) {
+            (0, 0) if !retried => RunOutcome::Succeeded,
+            (0, _) => RunOutcome::Partial,
+            _ => RunOutcome::Failed,
+        };
+        self.status_outcome = Some((outcome, Instant::now()));
+        self.status_message = match (results.len(), failed, skipped.len()) {
+            (count, _, skipped) if stopped => tr_fmt("Stopped: {0} commands ran, {1} skipped", &[&count, &skipped]),
+            (1, 0, 0) => tr("Command completed successfully"),
+            (1, _, 0) => tr("Command failed"),
+            (count, 0, _) => tr_fmt("All {0} commands completed successfully", &[&count]),
+            (count, failed, 0) => tr_fmt("{0} of {1} commands failed", &[&failed, &count]),
+            (count, failed, skipped) => tr_fmt("{0} of {1} commands failed, {2} skipped", &[&failed, &(count + skipped), &skipped]),
+        };
+        let severity = match outcome {
+            RunOutcome::Succeeded => Severity::Success,
+            RunOutcome::Partial => Severity::Warning,
+            RunOutcome::Failed => Severity::Error,
+        };
+        let text = match results.as_slice() {
+            [(name, _)] if skipped.is_empty() => format!("{}: {}", name, self.status_message),
+            _ => self.status_message.clone(),
+        };
+        self.toasts.push_run(severity, text);
+    }
+
+    fn trim_output_dock(&mut self) {
+        let excess = self.output_dock.len().saturating_sub(self.config.output_dock_max.max(1));
+        self.output_dock.drain(..excess);
+    }
+
+    fn copy_diagnostic_report(&mut self, ctx: &egui::Context) {
+        let last_run = (!self.command_output_plain.is_empty()).then(|| report::LastRun {
+            timing: &self.command_output_timing,
+            output: &self.command_output_plain,
+        });
+        let text = report::build(&self.config, last_run);
+        ctx.output_mut(|o| o.copied_text = text);
+        self.status_message = tr("Diagnostic report copied to clipboard");
+        self.toasts.push(Severity::Info, self.status_message.clone());
+    }
+
+    // Directories have no source, their preview lists what they contain
+    fn open_preview(&mut self, path: String, node: &ListNode, contents: Option<Vec<String>>) {
+        let missing = node.missing_dependencies();
+        let dependencies = node.dependencies.iter()
+            .map(|dependency| (dependency.clone(), !missing.contains(&dependency.as_str())))
+            .collect();
+
+        let source = match &node.command {
+            LinutilCommand::Raw(cmd) => cmd.clone(),
+            LinutilCommand::LocalFile { file, .. } => std::fs::read_to_string(file)
+                .unwrap_or_else(|e| format!("# Failed to read {}: {}", file.display(), e)),
+            LinutilCommand::None => String::new(),
+        };
+
+        let interpreter = match &node.command {
+            LinutilCommand::LocalFile { executable, args, .. } => {
+                Some((interpreter_name(executable, args), source.starts_with("#!")))
+            }
+            _ => None,
+        };
+        // Very large scripts start out as plain text, as do scripts in languages other than shell,
+        // which the highlighter doesn't know; highlighting can still be turned on manually
+        self.highlight_preview = source.len() <= highlight::MAX_HIGHLIGHT_BYTES
+            && interpreter.as_ref().is_none_or(|(name, _)| highlight::is_shell(name));
+        self.preview = Some(ScriptPreview {
+            json: command_json(&path, node, &source),
+            path,
+            name: node.name.clone(),
+            description: node.description.clone(),
+            task_list: node.task_list.clone(),
+            command_line: command_line(&node.command),
+            working_dir: build_process(&node.command, &ExecOptions { cwd: self.config.command_dir(), ..Default::default() })
+                .map(|process| working_dir(&process)),
+            script: match &node.command {
+                LinutilCommand::LocalFile { file, .. } => Some(absolute_script_args(&[], file).0),
+                _ => None,
+            },
+            interpreter,
+            dependencies,
+            verify: node.verify.clone(),
+            source,
+            highlighted: None,
+            env_draft: Default::default(),
+            contents,
+        });
+    }
+
+    // Indices into `tabs` in the order the sidebar shows them, hidden tabs included
+    fn sidebar_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = self.config.tab_order
+            .iter()
+            .filter_map(|name| self.tabs.iter().position(|tab| &tab.name == name))
+            .collect();
+        for i in 0..self.tabs.len() {
+            if !order.contains(&i) {
+                order.push(i);
+            }
+        }
+        order
+    }
+
+    // Installs a freshly loaded tab set, keeping the current tab by name when it still exists.
+    // Node ids of the old trees are meaningless in the new ones, so navigation starts over.
+    fn replace_tabs(&mut self, tabs: TabList) -> TabList {
+        let current_name = self.tabs.get(self.current_tab_index).map(|tab| tab.name.clone());
+        let old_tabs = std::mem::replace(&mut self.tabs, tabs);
+        self.shared_nodes.clear();
+
+        self.current_tab_index = current_name
+            .and_then(|name| self.tabs.iter().position(|tab| tab.name == name))
+            .unwrap_or(0);
+        self.tab_locations.clear();
+        self.visit_stack.clear();
+        if let Some(tab) = self.tabs.get(self.current_tab_index) {
+            self.visit_stack.push((tab.tree.root().id(), 0));
+        }
+        self.selected_index = 0;
+        self.update_items();
+        old_tabs
+    }
+
+    // Loads the tabs from scratch in the new mode, so compatibility is checked against the system
+    // as it is now rather than when the other mode was last loaded
+    fn apply_validation_mode(&mut self) {
+        if self.load_tabs() {
+            self.status_message = if self.config.validate {
+                tr("Showing only commands compatible with this system")
+            } else {
+                tr("Showing all commands")
+            };
+        }
+    }
+
+    fn set_validation(&mut self, validate: bool) {
+        self.config.validate = validate;
+        if let Err(e) = self.config.save() {
+            self.error_message = format!("Failed to save settings: {}", e);
+        }
+        self.apply_validation_mode();
+    }
+
+    fn rescan_system(&mut self) {
+        self.package_managers = backends::probe();
+        if self.load_tabs() {
+            self.status_message = tr("Re-scanned system compatibility");
+        }
+    }
+
+    fn switch_tab(&mut self, tab_index: usize) {
+        if tab_index < self.tabs.len() && tab_index != self.current_tab_index {
+            let location = (std::mem::take(&mut self.visit_stack), self.selected_index);
+            self.tab_locations.insert(self.current_tab_index, location);
+            self.status_outcome = None;
+
+            self.current_tab_index = tab_index;
+            // Go back to where this tab was left, or to its root on the first visit
+            let (visit_stack, selected_index) = self.tab_locations.remove(&tab_index).unwrap_or_else(|| {
+                (vec![(self.tabs[tab_index].tree.root().id(), 0)], 0)
+            });
+            self.visit_stack = visit_stack;
+            self.selected_index = selected_index;
+            self.search_text.clear();
+            self.update_items();
+            self.status_message = tr_fmt("Switched to {0}", &[&self.tabs[tab_index].name]);
+        }
+    }
+
+    fn handle_action(&mut self, ctx: &egui::Context, action: Action) {
+        // Keyboard actions work on the entries matching what was typed, even mid-debounce
+        if self.search_edited.is_some() {
+            self.apply_search_filter();
+        }
+        match action {
+            Action::ShowHelp => self.show_help = true,
+            Action::FocusSearch => self.focus_search = true,
+            Action::CommandPalette if self.palette.is_some() => self.palette = None,
+            Action::CommandPalette => self.open_palette(),
+            Action::Close if self.palette.is_some() => self.palette = None,
+            Action::TabSwitcher if self.tab_switcher.is_some() => self.tab_switcher = None,
+            Action::TabSwitcher => self.open_tab_switcher(),
+            Action::Close if self.tab_switcher.is_some() => self.tab_switcher = None,
+            Action::Close if ctx.memory(|m| m.has_focus(search_id())) => {
+                if self.search_text.is_empty() {
+                    ctx.memory_mut(|m| m.surrender_focus(search_id()));
+                } else {
+                    self.search_text.clear();
+                    self.apply_search_filter();
+                }
+            }
+            // Close one window per press, topmost first
+            Action::Close => {
+                if self.confirm_close.is_some() {
+                    self.confirm_close = None;
+                } else if self.show_help {
+                    self.show_help = false;
+                } else if self.schedule_draft.is_some() {
+                    self.schedule_draft = None;
+                } else if self.pending_run.is_some() {
+                    self.pending_run = None;
+                    self.status_message = tr("Execution cancelled");
+                } else if !self.error_message.is_empty() {
+                    self.error_message.clear();
+                } else if self.show_command_output {
+                    self.show_command_output = false;
+                } else if self.preview.is_some() {
+                    self.preview = None;
+                } else if self.show_selection {
+                    self.show_selection = false;
+                } else if self.self_test.is_some() {
+                    self.self_test = None;
+                } else if self.show_stats {
+                    self.show_stats = false;
+                } else {
+                    self.show_settings = false;
+                }
+            }
+            Action::SelectPrevious => {
+                self.selected_index = self.selected_index.saturating_sub(1);
+                self.scroll_to_selected = true;
+            }
+            Action::SelectNext => {
+                if self.selected_index + 1 < self.filtered.len() {
+                    self.selected_index += 1;
+                }
+                self.scroll_to_selected = true;
+            }
+            Action::Activate if self.pending_run.is_some() => self.confirm_pending_run(),
+            Action::Activate => match self.filtered_entry(self.selected_index) {
+                Some(entry) if entry.is_directory => self.enter_directory(),
+                Some(_) => self.execute_selected_command(),
+                None => {}
+            },
+            Action::GoBack => self.go_back(),
+            Action::ToggleSelection => {
+                let was_empty = self.selected_commands.is_empty();
+                self.toggle_multi_select();
+                self.multi_select = !self.selected_commands.is_empty();
+                // Keep the selection in view while building it from the keyboard
+                if was_empty && self.multi_select {
+                    self.show_selection = true;
+                }
+            }
+            Action::SelectAll => self.select_all_shown(),
+            Action::ToggleSelectionPanel => {
+                if self.selected_commands.is_empty() {
+                    self.status_message = tr("Nothing selected");
+                } else {
+                    self.show_selection = !self.show_selection;
+                }
+            }
+            Action::ExecuteSelection => {
+                if self.selected_commands.is_empty() {
+                    self.status_message = tr("Nothing selected");
+                } else if self.safe_mode() {
+                    self.status_message = tr(SAFE_MODE_HINT);
+                } else {
+                    self.execute_all_selected();
+                }
+            }
+            Action::Reload => self.reload_tabs(),
+            Action::SwitchTab(position) => {
+                if let Some(&tab_index) = self.visible_tabs().get(position) {
+                    self.switch_tab(tab_index);
+                }
+            }
+        }
+    }
+
+    // Asked when the window is closed with commands still running or queued
+    fn render_close_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(unfinished) = self.confirm_close else {
+            return;
+        };
+        let mut quit = false;
+        let mut stay = false;
+        egui::Window::new("⚠ Commands still running")
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                let message = if unfinished == 1 {
+                    "1 command has not finished yet.".to_string()
+                } else {
+                    format!("{} commands have not finished yet.", unfinished)
+                };
+                ui.label(egui::RichText::new(message)
+                        .color(self.theme.on_surface));
+                ui.label(egui::RichText::new("Closing stops the running commands and skips the rest of the queue, which can leave an install half done.")
+                        .color(self.theme.on_surface_variant));
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    if ui.add(egui::Button::new(egui::RichText::new("Stop and close")
+                            .color(egui::Color32::WHITE))
+                            .fill(self.theme.danger))
+                        .clicked() {
+                        quit = true;
+                    }
+                    if ui.button("Keep running").clicked() {
+                        stay = true;
+                    }
+                });
+            });
+
+        if quit {
+            self.close_confirmed = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        if quit || stay {
+            self.confirm_close = None;
+        }
+    }
+
+    // Full page message in place of the grid, with a button to try again. Returns whether the button
+    // was clicked
+    fn render_state_message(&self, ui: &mut egui::Ui, icon: &str, title: &str, detail: &str, button: &str) -> bool {
+        let mut clicked = false;
+        ui.vertical_centered(|ui| {
+            ui.add_space(ui.available_height() / 3.0);
+            ui.label(egui::RichText::new(icon)
+                    .size(48.0)
+                    .color(self.theme.on_surface_variant));
+            ui.add_space(16.0);
+            ui.label(egui::RichText::new(title)
+                    .size(18.0)
+                    .strong()
+                    .color(self.theme.on_surface));
+            ui.add_space(8.0);
+            ui.label(egui::RichText::new(detail)
+                    .color(self.theme.on_surface_variant));
+            ui.add_space(16.0);
+            clicked = ui.button(button).clicked();
+        });
+        clicked
+    }
+
+    fn render_modern_button(&self, ui: &mut egui::Ui, text: &str, icon: &str, color: egui::Color32) -> egui::Response {
+        let button_height = 32.0;
+        let (rect, response) = ui.allocate_exact_size(
+            egui::vec2(ui.available_width(), button_height),
+            egui::Sense::click()
+        );
+
+        if ui.is_rect_visible(rect) {
+            let visuals = ui.style().interact(&response);
+            let bg_color = if response.hovered() {
+                color.gamma_multiply(1.2)
+            } else {
+                color
+            };
+
+            ui.painter().rect_filled(rect, visuals.rounding, bg_color);
+            
+            let text_color = if response.hovered() {
+                egui::Color32::WHITE
+            } else {
+                self.theme.on_surface
+            };
+
+            ui.painter().text(
+                rect.left_center() + egui::vec2(12.0, 0.0),
+                egui::Align2::LEFT_CENTER,
+                format!("{} {}", icon, text),
+                egui::FontId::proportional(14.0),
+                text_color,
+            );
+        }
+
+        response
+    }
+
+    fn render_category_card(&self, ui: &mut egui::Ui, entry: &ListEntry, index: usize) -> Option<String> {
+        let mut action = None;
+        
+        let is_multi_selected = self.selected_commands.iter().any(|selected| selected.is_same(entry));
+        let is_focused = index == self.selected_index;
+        // Latest queued run of this command; finished ones only matter while their batch runs
+        let run_status = self.queue.iter().rev()
+            .find(|item| item.path == entry.path)
+            .map(|item| item.status)
+            .filter(|status| self.executing_command || !status.is_finished());
+        let border = if is_focused {
+            egui::Stroke::new(2.0, if entry.node.danger { self.theme.danger } else { self.theme.primary })
+        } else if entry.node.danger {
+            egui::Stroke::new(1.0, self.theme.danger)
+        } else {
+            egui::Stroke::new(1.0, self.theme.border)
+        };
+        
+        // Card styling
+        let card_color = if is_multi_selected {
+            self.theme.primary.gamma_multiply(0.3)
+        } else {
+            self.theme.surface
+        };
+
+        let compact = self.config.list_density == ListDensity::Compact;
+
+        // The scope senses clicks behind the card's buttons, for the context menu
+        let response = ui.scope_builder(egui::UiBuilder::new().sense(egui::Sense::click()), |ui| {
+            egui::Frame::none()
+                .fill(card_color)
+                .rounding(if compact { 6.0 } else { 12.0 })
+                .inner_margin(if compact { egui::Margin::symmetric(10.0, 4.0) } else { egui::Margin::same(16.0) })
+                .stroke(border)
+                .show(ui, |ui| {
+                    // Incompatible commands stay visible and runnable, just greyed out
+                    if !entry.node.is_compatible() {
+                        ui.multiply_opacity(0.5);
+                    }
+                    let missing_backend = self.missing_backends.get(&entry.path);
+                    if missing_backend.is_some() {
+                        ui.multiply_opacity(0.5);
+                    }
+                    if run_status == Some(QueueStatus::Running) {
+                        ui.multiply_opacity(0.7);
+                    }
+                    if compact {
+                        action = self.render_compact_row(ui, entry, index, is_multi_selected, run_status, missing_backend);
+                        return;
+                    }
+                    ui.vertical(|ui| {
+                        // Header with icon and title
+                        ui.horizontal(|ui| {
+                            let icons = self.config.icon_theme;
+                            let icon = if entry.is_directory { icons.dir_icon() } else { icons.cmd_icon() };
+                            let status_icon = if is_multi_selected { icons.multi_select_icon() } else { "" };
+                            
+                            ui.label(egui::RichText::new(format!("{} {}{}", icon, entry.node.name, status_icon))
+                                    .size(16.0)
+                                    .strong()
+                                    .color(self.theme.on_surface));
+                                    
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                self.render_run_status(ui, run_status);
+                                if !entry.node.task_list.is_empty() {
+                                    ui.label(egui::RichText::new(&entry.node.task_list)
+                                            .size(10.0)
+                                            .background_color(self.theme.secondary.gamma_multiply(0.3))
+                                            .color(self.theme.on_surface_variant));
+                                }
+                            });
+                        });
+
+                        if let Some(manager) = missing_backend {
+                            ui.label(egui::RichText::new(tr_fmt("⚠ Needs {0}", &[&manager.binary()]))
+                                    .size(11.0)
+                                    .color(self.theme.warning))
+                                .on_hover_text(tr_fmt("This command is written for {0}, which is not installed", &[&manager.binary()]));
+                        }
+
+                        // Where a search result from outside the current folder lives
+                        if self.filtered.get(index).is_some_and(|&item| item >= self.folder_len) {
+                            ui.label(egui::RichText::new(&entry.path)
+                                    .size(11.0)
+                                    .color(self.theme.on_surface_variant));
+                        }
+
+                        ui.add_space(8.0);
+
+                        // Description
+                        if !entry.node.description.is_empty() {
+                            if self.config.markdown_descriptions {
+                                ui.label(markdown::render(&entry.node.description, &self.theme, 13.0, self.theme.on_surface_variant));
+                            } else {
+                                ui.label(egui::RichText::new(&entry.node.description)
+                                        .size(13.0)
+                                        .color(self.theme.on_surface_variant));
+                            }
+                            ui.add_space(12.0);
+                        }
+
+                        if let Some(note) = self.notes.get(&entry.path) {
+                            ui.label(egui::RichText::new(format!("📝 {}", note.lines().next().unwrap_or_default()))
+                                    .size(11.0)
+                                    .italics()
+                                    .color(self.theme.on_surface_variant))
+                                .on_hover_text(note);
+                            ui.add_space(8.0);
+                        }
+
+                        if let Some(last_run) = self.last_runs.get(&entry.path).filter(|_| !entry.is_directory) {
+                            let (text, color) = if last_run.success {
+                                (format!("✔ ran {}", format_ago(last_run.finished_at)), self.theme.success)
+                            } else {
+                                (format!("✘ failed {}", format_ago(last_run.finished_at)), self.theme.danger)
+                            };
+                            ui.label(egui::RichText::new(text)
+                                    .size(11.0)
+                                    .color(color.gamma_multiply(0.8)));
+                            ui.add_space(8.0);
+                        }
+
+                        // Requirements from the tab data's preconditions
+                        if !entry.node.requirements.is_empty() {
+                            ui.horizontal_wrapped(|ui| {
+                                for requirement in &entry.node.requirements {
+                                    let (icon, color) = if requirement.met { ("✔", self.theme.success) } else { ("✘", self.theme.danger) };
+                                    ui.label(egui::RichText::new(format!("{} {}", icon, requirement.description))
+                                            .size(10.0)
+                                            .background_color(color.gamma_multiply(0.3))
+                                            .color(self.theme.on_surface_variant));
+                                }
+                            });
+                            ui.add_space(8.0);
+                        }
+
+                        // Action buttons
+                        ui.horizontal(|ui| {
+                            if entry.is_directory {
+                                // Right to left, so Open takes whatever width the menu leaves
+                                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                    ui.add_enabled_ui(!self.safe_mode(), |ui| {
+                                        ui.menu_button("▶ Run all", |ui| {
+                                            if ui.button("Commands in this folder").clicked() {
+                                                action = Some("run_folder".to_string());
+                                                ui.close_menu();
+                                            }
+                                            if ui.button("Including subfolders").clicked() {
+                                                action = Some("run_folder_recursive".to_string());
+                                                ui.close_menu();
+                                            }
+                                        })
+                                    }).response.on_disabled_hover_text(tr(SAFE_MODE_HINT));
+                                    if self.render_modern_button(ui, "Open", "📂", self.theme.primary).clicked() {
+                                        action = Some("enter".to_string());
+                                    }
+                                });
+                            } else {
+                                let execute = ui.add_enabled_ui(!self.safe_mode(), |ui| {
+                                    self.render_modern_button(ui, "Execute", "▶️", self.theme.success)
+                                }).inner;
+                                if execute.on_disabled_hover_text(tr(SAFE_MODE_HINT)).clicked() {
+                                    action = Some("execute".to_string());
+                                }
+                                
+                                ui.add_space(8.0);
+                                
+                                if self.render_modern_button(ui, "Preview", "👁️", self.theme.secondary).clicked() {
+                                    action = Some("preview".to_string());
+                                }
+
+                                if entry.node.multi_select {
+                                    ui.add_space(8.0);
+                                    let multi_text = if is_multi_selected { "Deselect" } else { "Select" };
+                                    if self.render_modern_button(ui, multi_text, "☑️", self.theme.accent).clicked() {
+                                        action = Some("multi_select".to_string());
+                                    }
+                                }
+                            }
+                        });
+                    });
+                })
+        }).response;
+
+        // Everything the card offers, plus the actions that don't get a button of their own
+        response.context_menu(|ui| {
+            let safe_mode = self.safe_mode();
+            let mut item = |ui: &mut egui::Ui, enabled: bool, text: &str, name: &str| {
+                if ui.add_enabled(enabled, egui::Button::new(text))
+                    .on_disabled_hover_text(tr(SAFE_MODE_HINT))
+                    .clicked()
+                {
+                    action = Some(name.to_string());
+                    ui.close_menu();
+                }
+            };
+            if entry.is_directory {
+                item(ui, true, "📂 Open", "enter");
+                item(ui, true, "👁 Preview", "preview");
+                ui.separator();
+                item(ui, !safe_mode, "▶ Run all in this folder", "run_folder");
+                item(ui, !safe_mode, "▶ Run all including subfolders", "run_folder_recursive");
+                return;
+            }
+            item(ui, !safe_mode, "▶ Execute", "execute");
+            item(ui, true, "👁 Preview", "preview");
+            item(ui, !safe_mode, "⏰ Schedule…", "schedule");
+            ui.separator();
+            item(ui, true, "📋 Copy command", "copy_command");
+            if matches!(entry.node.command, LinutilCommand::LocalFile { .. }) {
+                item(ui, true, "📂 Show in folder", "show_in_folder");
+            }
+            if entry.node.multi_select {
+                ui.separator();
+                let text = if is_multi_selected { "☑ Remove from selection" } else { "☑ Add to selection" };
+                item(ui, true, text, "multi_select");
+            }
+        });
+        
+        // Add hover effect to entire card
+        if response.hovered() {
+            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+        }
+
+        if !entry.node.is_compatible() {
+            let unmet: Vec<&str> = entry.node.requirements
+                .iter()
+                .filter(|requirement| !requirement.met)
+                .map(|requirement| requirement.description.as_str())
+                .collect();
+            response.clone().on_hover_text(format!(
+                "Not compatible with this system, requires: {}\nIt can still be run, but will likely fail.",
+                unmet.join(", ")
+            ));
+        }
+
+        if is_focused && self.scroll_to_selected {
+            response.scroll_to_me(None);
+        }
+
+        action
+    }
+
+    // Spinner while the entry runs, or a dot in the color of how its last queued run went
+    fn render_run_status(&self, ui: &mut egui::Ui, run_status: Option<QueueStatus>) {
+        match run_status {
+            Some(QueueStatus::Running) => {
+                ui.add(egui::Spinner::new().size(14.0)).on_hover_text(tr("Running"));
+            }
+            Some(status) => {
+                let color = match status {
+                    QueueStatus::Succeeded => self.theme.success,
+                    QueueStatus::Failed => self.theme.danger,
+                    QueueStatus::Pending => self.theme.warning,
+                    _ => self.theme.on_surface_variant,
+                };
+                ui.label(egui::RichText::new("●").color(color)).on_hover_text(status.label());
+            }
+            None => {}
+        }
+    }
+
+    // The compact density's line for an entry: the same actions as the card's buttons, with
+    // what the card shows below the title reduced to icons and tooltips
+    fn render_compact_row(
+        &self,
+        ui: &mut egui::Ui,
+        entry: &ListEntry,
+        index: usize,
+        is_multi_selected: bool,
+        run_status: Option<QueueStatus>,
+        missing_backend: Option<&backends::PackageManager>,
+    ) -> Option<String> {
+        let mut action = None;
+        ui.horizontal(|ui| {
+            let icons = self.config.icon_theme;
+            let icon = if entry.is_directory { icons.dir_icon() } else { icons.cmd_icon() };
+            let status_icon = if is_multi_selected { icons.multi_select_icon() } else { "" };
+            let name = ui.label(egui::RichText::new(format!("{} {}{}", icon, entry.node.name, status_icon))
+                    .size(14.0)
+                    .strong()
+                    .color(self.theme.on_surface));
+            // Where a search result from outside the current folder lives
+            if self.filtered.get(index).is_some_and(|&item| item >= self.folder_len) {
+                name.on_hover_text(&entry.path);
+            }
+
+            // Right to left, so the description takes whatever width the rest leaves
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if entry.is_directory {
+                    ui.add_enabled_ui(!self.safe_mode(), |ui| {
+                        ui.menu_button("▶ Run all", |ui| {
+                            if ui.button("Commands in this folder").clicked() {
+                                action = Some("run_folder".to_string());
+                                ui.close_menu();
+                            }
+                            if ui.button("Including subfolders").clicked() {
+                                action = Some("run_folder_recursive".to_string());
+                                ui.close_menu();
+                            }
+                        })
+                    }).response.on_disabled_hover_text(tr(SAFE_MODE_HINT));
+                    if ui.button("📂 Open").clicked() {
+                        action = Some("enter".to_string());
+                    }
+                } else {
+                    // Added in reverse, so they read in the same order as on the card
+                    if entry.node.multi_select {
+                        let multi_text = if is_multi_selected { "☑ Deselect" } else { "☑ Select" };
+                        if ui.button(multi_text).clicked() {
+                            action = Some("multi_select".to_string());
+                        }
+                    }
+                    if ui.button("👁 Preview").clicked() {
+                        action = Some("preview".to_string());
+                    }
+                    if ui.add_enabled(!self.safe_mode(), egui::Button::new("▶ Execute"))
+                        .on_disabled_hover_text(tr(SAFE_MODE_HINT))
+                        .clicked()
+                    {
+                        action = Some("execute".to_string());
+                    }
+                }
+
+                self.render_run_status(ui, run_status);
+                if let Some(last_run) = self.last_runs.get(&entry.path).filter(|_| !entry.is_directory) {
+                    let (icon, color, text) = if last_run.success {
+                        ("✔", self.theme.success, format!("ran {}", format_ago(last_run.finished_at)))
+                    } else {
+                        ("✘", self.theme.danger, format!("failed {}", format_ago(last_run.finished_at)))
+                    };
+                    ui.label(egui::RichText::new(icon)
+                            .size(11.0)
+                            .color(color.gamma_multiply(0.8)))
+                        .on_hover_text(text);
+                }
+                if let Some(note) = self.notes.get(&entry.path) {
+                    ui.label(egui::RichText::new("📝")
+                            .size(11.0)
+                            .color(self.theme.on_surface_variant))
+                        .on_hover_text(note);
+                }
+                if let Some(manager) = missing_backend {
+                    ui.label(egui::RichText::new("⚠")
+                            .size(11.0)
+                            .color(self.theme.warning))
+                        .on_hover_text(tr_fmt("This command is written for {0}, which is not installed", &[&manager.binary()]));
+                }
+                if !entry.node.task_list.is_empty() {
+                    ui.label(egui::RichText::new(&entry.node.task_list)
+                            .size(10.0)
+                            .background_color(self.theme.secondary.gamma_multiply(0.3))
+                            .color(self.theme.on_surface_variant));
+                }
+
+                // First line of the description, cut to the width that is left
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                    let first_line = entry.node.description.lines().next().unwrap_or_default();
+                    let description = if self.config.markdown_descriptions {
+                        egui::Label::new(markdown::render(first_line, &self.theme, 12.0, self.theme.on_surface_variant))
+                    } else {
+                        egui::Label::new(egui::RichText::new(first_line)
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant))
+                    };
+                    let response = ui.add(description.truncate());
+                    if !entry.node.description.is_empty() {
+                        response.on_hover_text(&entry.node.description);
+                    }
+                });
+            });
+        });
+        action
+    }
+}
+
+// Closing the window must not leave commands running without anyone to report to: the rest of the
+// queue is skipped, running processes are killed and the worker is waited for
+impl Drop for LinutilApp {
+    fn drop(&mut self) {
+        stop_worker(self.command_tx.take(), self.worker.take());
+    }
+}
+
+// Numbers the jobs from next_id on and queues them in the order given, which is the order the
+// worker starts them in. Returns the queue for the worker and the entries of the queue panel
+fn queue_jobs(mut jobs: Vec<Job>, next_id: &mut u64) -> (queue::JobQueue, Vec<QueueItem>) {
+    for job in &mut jobs {
+        job.id = *next_id;
+        *next_id += 1;
+    }
+    let queue: queue::JobQueue = Arc::new(Mutex::new(VecDeque::new()));
+    let items = jobs.iter()
+        .map(|job| QueueItem::new(job.id, job.path.clone(), queue.clone()))
+        .collect();
+    queue.lock().unwrap().extend(jobs);
+    (queue, items)
+}
+
+// Spawns the thread running the batches sent to it, which ends once the sender is dropped
+fn spawn_worker(batches: mpsc::Receiver<QueuedBatch>, events: mpsc::Sender<CommandEvent>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        while let Ok(batch) = batches.recv() {
+            run_batch(batch, &events);
+        }
+    })
+}
+
+// Skips the rest of the queue, kills the running processes and waits for the worker to finish.
+// A process that left its group can still hold the output pipes open and keep the worker reading,
+// so the wait is bounded; returns whether the worker finished within it
+fn stop_worker(command_tx: Option<mpsc::Sender<QueuedBatch>>, worker: Option<thread::JoinHandle<()>>) -> bool {
+    SHUTTING_DOWN.store(true, Ordering::Relaxed);
+    drop(command_tx);
+    for (_, child) in std::mem::take(&mut *RUNNING_CHILDREN.lock().unwrap()) {
+        kill_process_group(child);
+    }
+    let Some(worker) = worker else {
+        return true;
+    };
+    let deadline = Instant::now() + WORKER_SHUTDOWN_WAIT;
+    while !worker.is_finished() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    // Otherwise the thread is left behind, the process is about to exit anyway
+    worker.is_finished() && worker.join().is_ok()
+}
+
+impl eframe::App for LinutilApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // The loading screen has been shown by now
+        if self.loading && ctx.cumulative_pass_nr() > 0 {
+            self.load_tabs();
+        }
+
+        // Check for command execution results
+        self.check_command_result();
+
+        // Closing kills whatever is running (see the Drop impl), so ask first
+        if ctx.input(|i| i.viewport().close_requested()) && !self.close_confirmed && self.config.confirm_close {
+            let unfinished = self.queue.iter().filter(|item| !item.status.is_finished()).count();
+            if unfinished > 0 || self.executing_command {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.confirm_close = Some(unfinished.max(1));
+            }
+        }
+
+        // Start over for the next person once nobody has used the app for a while. A run in
+        // progress is left alone, its output is still of interest to whoever started it
+        if ctx.input(|i| !i.events.is_empty()) {
+            self.last_input = Instant::now();
+        }
+        if self.config.idle_reset_mins > 0 && !self.executing_command {
+            match Duration::from_secs(self.config.idle_reset_mins * 60).checked_sub(self.last_input.elapsed()) {
+                Some(left) => ctx.request_repaint_after(left),
+                None => {
+                    self.reset_after_idle();
+                    self.last_input = Instant::now();
+                }
+            }
+        }
+
+        // Wake up for the next scheduled command even when nothing else is happening
+        if let Some(wait) = self.run_due_schedules() {
+            ctx.request_repaint_after(wait);
+        }
+
+        // Filter once typing pauses; the repaint makes sure the last keystroke gets its pass
+        if let Some(edited) = self.search_edited {
+            match SEARCH_DEBOUNCE.checked_sub(edited.elapsed()) {
+                Some(wait) if !wait.is_zero() => ctx.request_repaint_after(wait),
+                _ => self.apply_search_filter(),
+            }
+        }
+
+        // Force repaint for loading states
+        if self.loading || self.executing_command {
+            ctx.request_repaint();
+        }
+
+        let size = ctx.screen_rect().size();
+        if !self.config.size_bypass && (size.x < MIN_WINDOW_SIZE.x || size.y < MIN_WINDOW_SIZE.y) {
+            let mut bypass = false;
+            egui::CentralPanel::default().show(ctx, |ui| {
+                let detail = tr_fmt("The window is {0}×{1}, but needs at least {2}×{3}. Enlarge it, or show the app anyway.", &[
+                    &size.x.round(), &size.y.round(), &MIN_WINDOW_SIZE.x, &MIN_WINDOW_SIZE.y,
+                ]);
+                bypass = self.render_state_message(ui, "↔", &tr("Window too small"), &detail, &tr("Show anyway"));
+            });
+            if bypass {
+                self.config.size_bypass = true;
+                if let Err(e) = self.config.save() {
+                    self.error_message = format!("Failed to save settings: {}", e);
+                }
+            }
+            // Closing was cancelled to ask first, so the question has to be shown here as well
+            self.render_close_confirmation(ctx);
+            return;
+        }
+
+        // The palette and the tab switcher read Ctrl+Enter themselves
+        let overlay = self.palette.is_some() || self.tab_switcher.is_some();
+        for action in self.keymap.pressed(ctx, |action| !(overlay && action == Action::ExecuteSelection)) {
+            self.handle_action(ctx, action);
+        }
+
+        // Modern top bar
+        egui::TopBottomPanel::top("top_panel")
+            .min_height(64.0)
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    // Logo and title
+                    ui.label(egui::RichText::new("🐧 Linutil")
+                            .size(24.0)
+                            .strong()
+                            .color(self.theme.primary));
+                    ui.label(egui::RichText::new(tr("System Management Suite"))
+                            .size(16.0)
+                            .color(self.theme.on_surface_variant));
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        // Toggle sidebar button
+                        let (icon, hint) = match self.config.sidebar {
+                            SidebarMode::Full => ("◀", "Collapse the sidebar to icons"),
+                            SidebarMode::Rail => ("⏴", "Hide the sidebar"),
+                            SidebarMode::Hidden => ("▶", "Show the sidebar"),
+                        };
+                        if ui.button(icon).on_hover_text(tr(hint)).clicked() {
+                            self.config.sidebar = self.config.sidebar.next();
+                            if let Err(e) = self.config.save() {
+                                self.error_message = format!("Failed to save settings: {}", e);
+                            }
+                        }
+
+                        let (icon, hint) = match self.config.list_density {
+                            ListDensity::Cards => ("☰", "Show the entries as a compact list"),
+                            ListDensity::Compact => ("▦", "Show the entries as cards"),
+                        };
+                        if ui.button(icon).on_hover_text(tr(hint)).clicked() {
+                            self.config.list_density = match self.config.list_density {
+                                ListDensity::Cards => ListDensity::Compact,
+                                ListDensity::Compact => ListDensity::Cards,
+                            };
+                            if let Err(e) = self.config.save() {
+                                self.error_message = format!("Failed to save settings: {}", e);
+                            }
+                        }
+
+                        if ui.button(format!("⚙ {}", tr("Settings"))).clicked() {
+                            self.show_settings = !self.show_settings;
+                        }
+
+                        if self.safe_mode() {
+                            ui.label(egui::RichText::new(format!("🔒 {}", tr("Safe mode")))
+                                    .strong()
+                                    .color(self.theme.warning))
+                                .on_hover_text(tr(SAFE_MODE_HINT));
+                        }
+
+                        if ui.button("?").on_hover_text(tr_fmt("Keyboard shortcuts ({0})", &[&self.keymap.hint(ctx, Action::ShowHelp)])).clicked() {
+                            self.show_help = true;
+                        }
+
+                        if ui.button(format!("📊 {}", tr("Stats"))).clicked() {
+                            self.show_stats = !self.show_stats;
+                        }
+
+                        if !self.config.env_profiles.is_empty() {
+                            let mut profile = self.config.env_profile.clone();
+                            let selected = if profile.is_empty() { tr("No profile") } else { profile.clone() };
+                            egui::ComboBox::from_id_salt("env_profile_switch")
+                                .selected_text(format!("🌐 {}", selected))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut profile, String::new(), tr("No profile"));
+                                    for name in self.config.env_profiles.keys() {
+                                        ui.selectable_value(&mut profile, name.clone(), name);
+                                    }
+                                })
+                                .response
+                                .on_hover_text(tr("Environment profile used for commands"));
+                            if profile != self.config.env_profile {
+                                self.status_message = if profile.is_empty() {
+                                    tr("Environment profile turned off")
+                                } else {
+                                    tr_fmt("Switched to environment profile {0}", &[&profile])
+                                };
+                                self.config.env_profile = profile;
+                                if let Err(e) = self.config.save() {
+                                    self.error_message = format!("Failed to save settings: {}", e);
+                                }
+                            }
+                        }
+
+                        if !self.recent.is_empty() {
+                            let mut reveal = None;
+                            let mut rerun = None;
+                            ui.menu_button(format!("🕘 {}", tr("Recent")), |ui| {
+                                for path in &self.recent {
+                                    ui.horizontal(|ui| {
+                                        if ui.add_enabled(!self.safe_mode(), egui::Button::new("▶").small())
+                                            .on_hover_text("Run again")
+                                            .on_disabled_hover_text(tr(SAFE_MODE_HINT))
+                                            .clicked() {
+                                            rerun = Some(path.clone());
+                                            ui.close_menu();
+                                        }
+                                        if ui.selectable_label(false, path).on_hover_text("Go to the command").clicked() {
+                                            reveal = Some(path.clone());
+                                            ui.close_menu();
+                                        }
+                                    });
+                                }
+                            });
+                            if let Some(path) = reveal {
+                                if self.reveal_command(&path).is_none() {
+                                    self.status_message = tr_fmt("{0} is no longer available", &[&path]);
+                                }
+                            }
+                            if let Some(path) = rerun {
+                                match self.reveal_command(&path) {
+                                    Some(node) => {
+                                        let job = self.job(path, &node);
+                                        self.request_run(vec![job], 1, false);
+                                    }
+                                    None => self.status_message = tr_fmt("{0} is no longer available", &[&path]),
+                                }
+                            }
+                        }
+
+                        let reload_hint = tr_fmt("Reload the tab data ({0})", &[&self.keymap.hint(ctx, Action::Reload)]);
+                        if ui.add_enabled(!self.executing_command, egui::Button::new(format!("🔄 {}", tr("Reload"))))
+                            .on_hover_text(reload_hint)
+                            .on_disabled_hover_text(tr("Wait for the running commands to finish"))
+                            .clicked()
+                        {
+                            self.reload_tabs();
+                        }
+                        
+                        ui.add_space(16.0);
+                        
+                        if !self.scheduled.is_empty()
+                            && ui.button(format!("⏰ {}", self.scheduled.len())).on_hover_text(tr("Scheduled commands")).clicked() {
+                            self.show_scheduled = !self.show_scheduled;
+                        }
+
+                        if !self.queue.is_empty() {
+                            let active = self.queue.iter().filter(|item| !item.status.is_finished()).count();
+                            if ui.button(format!("📋 {} ({})", tr("Queue"), active)).clicked() {
+                                self.show_queue = !self.show_queue;
+                            }
+                        }
+
+                        // Execution status
+                        if self.executing_command {
+                            ui.spinner();
+                            ui.label(egui::RichText::new(tr("Executing..."))
+                                    .color(self.theme.warning));
+                            if ui.add_enabled(!STOPPING.load(Ordering::Relaxed), egui::Button::new(format!("⏹ {}", tr("Stop"))))
+                                .on_hover_text("Kill the running commands and skip everything still queued")
+                                .clicked() {
+                                self.stop_run();
+                            }
+                        }
                         
-                        ui.label(egui::RichText::new(format!("{} {}{}", icon, entry.node.name, status_icon))
+                        // Multi-select indicator
+                        if !self.selected_commands.is_empty() {
+                            let selection_label = ui.add(egui::Button::new(
+                                egui::RichText::new(tr_fmt("{0} selected", &[&self.selected_commands.len()]))
+                                    .color(self.theme.on_surface))
+                                .fill(self.theme.accent.gamma_multiply(0.3)))
+                                .on_hover_text(format!("{} ({})", tr("Review the selection"),
+                                    self.keymap.hint(ctx, Action::ToggleSelectionPanel)));
+                            if selection_label.clicked() {
+                                self.show_selection = !self.show_selection;
+                            }
+                            
+                            if ui.add_enabled(!self.safe_mode(), egui::Button::new(tr("Execute All")))
+                                .on_hover_text(self.keymap.hint(ctx, Action::ExecuteSelection))
+                                .on_disabled_hover_text(tr(SAFE_MODE_HINT))
+                                .clicked() {
+                                self.execute_all_selected();
+                            }
+                        }
+                    });
+                });
+                
+                ui.add_space(8.0);
+                
+                // Navigation bar
+                ui.horizontal(|ui| {
+                    // Breadcrumb
+                    ui.label(egui::RichText::new("📍")
+                            .color(self.theme.accent));
+                    ui.label(egui::RichText::new(&self.breadcrumb)
+                            .size(14.0)
+                            .color(self.theme.on_surface_variant));
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        // Back button
+                        if !self.at_root() {
+                            if ui.button(format!("⬅ {}", tr("Back"))).clicked() {
+                                self.go_back();
+                            }
+                        }
+                        
+                        ui.add_space(16.0);
+                        
+                        // Search
+                        ui.label("🔍");
+                        let search_response = ui.add_sized([200.0, 24.0], 
+                            egui::TextEdit::singleline(&mut self.search_text)
+                                .id(search_id())
+                                .hint_text(tr_fmt("Search utilities... ({0})", &[&self.keymap.hint(ctx, Action::FocusSearch)])));
+                        if self.focus_search {
+                            search_response.request_focus();
+                            self.focus_search = false;
+                        }
+                        if search_response.changed() {
+                            if self.current_items.len() > DEBOUNCE_ITEMS {
+                                self.search_edited = Some(Instant::now());
+                            } else {
+                                self.apply_search_filter();
+                            }
+                        }
+
+                        let mut scope = self.search_scope;
+                        egui::ComboBox::from_id_salt("search_scope")
+                            .selected_text(tr(scope.label()))
+                            .show_ui(ui, |ui| {
+                                for option in SearchScope::ALL {
+                                    ui.selectable_value(&mut scope, option, tr(option.label()));
+                                }
+                            });
+                        if scope != self.search_scope {
+                            self.search_scope = scope;
+                            self.update_items();
+                        }
+
+                        if !self.task_tags.is_empty() {
+                            let mut tag_filter = self.tag_filter.clone();
+                            let all_tags = tr("All tags");
+                            egui::ComboBox::from_id_salt("task_tag_filter")
+                                .selected_text(tag_filter.as_deref().unwrap_or(&all_tags))
+                                .show_ui(ui, |ui| {
+                                    ui.selectable_value(&mut tag_filter, None, &all_tags);
+                                    for tag in &self.task_tags {
+                                        ui.selectable_value(&mut tag_filter, Some(tag.clone()), tag);
+                                    }
+                                });
+                            if tag_filter != self.tag_filter {
+                                self.tag_filter = tag_filter;
+                                self.apply_search_filter();
+                            }
+                        }
+                    });
+                });
+                ui.add_space(8.0);
+            });
+
+        // Without validation every command is shown, whether it can work here or not
+        if !self.config.validate && !self.tabs.is_empty() {
+            let mut validate = false;
+            egui::TopBottomPanel::top("validation_banner")
+                .frame(egui::Frame::none()
+                    .fill(self.theme.warning.gamma_multiply(0.15))
+                    .inner_margin(egui::Margin::symmetric(16.0, 6.0)))
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(format!("⚠ {}", tr("Showing all commands, including ones that are incompatible with this system and may not work")))
+                                .color(self.theme.warning));
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            validate = ui.add_enabled(!self.executing_command, egui::Button::new(tr("Hide incompatible commands")))
+                                .on_disabled_hover_text(tr("Wait for the running commands to finish"))
+                                .clicked();
+                        });
+                    });
+                });
+            if validate {
+                self.set_validation(true);
+            }
+        }
+
+        // Status bar
+        if let Some((_, since)) = self.status_outcome {
+            match STATUS_OUTCOME_DURATION.checked_sub(since.elapsed()) {
+                Some(left) => ctx.request_repaint_after(left),
+                None => self.status_outcome = None,
+            }
+        }
+        if let Some(since) = self.output_toast {
+            match Duration::from_secs(self.config.toast_secs).checked_sub(since.elapsed()) {
+                Some(left) => ctx.request_repaint_after(left),
+                None => self.output_toast = None,
+            }
+        }
+        let status_color = match self.status_outcome {
+            Some((RunOutcome::Succeeded, _)) => self.theme.success,
+            Some((RunOutcome::Partial, _)) => self.theme.warning,
+            Some((RunOutcome::Failed, _)) => self.theme.danger,
+            None => self.theme.on_surface_variant,
+        };
+        if !self.config.show_toasts {
+            self.toasts.clear();
+        } else if self.toasts.show(ctx, &self.theme) {
+            self.show_command_output = true;
+            self.output_toast = None;
+        }
+        let mut rerun = None;
+        let mut toggle_validation = None;
+        let mut toggle_dock = false;
+        egui::TopBottomPanel::bottom("status_panel")
+            .min_height(32.0)
+            .show(ctx, |ui| {
+                ui.add_space(4.0);
+                ui.horizontal(|ui| {
+                    if self.output_toast.is_some() {
+                        egui::Frame::none()
+                            .fill(self.theme.success.gamma_multiply(0.2))
+                            .rounding(6.0)
+                            .inner_margin(egui::Margin::symmetric(8.0, 2.0))
+                            .show(ui, |ui| {
+                                ui.label(egui::RichText::new(format!("✔ {}", self.status_message))
+                                        .size(12.0)
+                                        .color(self.theme.success));
+                                if ui.small_button(tr("Show output")).clicked() {
+                                    self.show_command_output = true;
+                                    self.output_toast = None;
+                                }
+                            });
+                    } else {
+                        ui.label(egui::RichText::new(&self.status_message)
+                                .size(12.0)
+                                .color(status_color));
+                    }
+                    if let Some((path, node)) = self.last_executed_node.as_ref().filter(|_| !self.executing_command && !self.safe_mode()) {
+                        if ui.small_button(format!("↻ {}", tr("Re-run"))).on_hover_text(&node.name).clicked() {
+                            rerun = Some((path.clone(), node.clone()));
+                        }
+                    }
+                    
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        ui.label(egui::RichText::new(tr_fmt("{0} items", &[&self.filtered.len()]))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant));
+                        ui.separator();
+                        let dock_label = egui::RichText::new(format!("📜 {} ({})", tr("Output"), self.output_dock.len()))
+                            .size(12.0)
+                            .color(self.theme.on_surface_variant);
+                        if ui.add(egui::SelectableLabel::new(self.config.output_dock, dock_label))
+                            .on_hover_text(tr("Show or hide the results of this session below the commands"))
+                            .clicked()
+                        {
+                            toggle_dock = true;
+                        }
+                        ui.separator();
+                        let label = if self.config.validate {
+                            egui::RichText::new(format!("🛡 {}", tr("Compatible commands only")))
+                                .size(12.0)
+                                .color(self.theme.success)
+                        } else {
+                            egui::RichText::new(tr("All commands (not validated)"))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant)
+                        };
+                        if ui.add_enabled(!self.executing_command, egui::SelectableLabel::new(self.config.validate, label))
+                            .on_hover_text(tr("Click to toggle hiding commands incompatible with this system"))
+                            .clicked()
+                        {
+                            toggle_validation = Some(!self.config.validate);
+                        }
+                    });
+                });
+                ui.add_space(4.0);
+            });
+        if let Some((path, node)) = rerun {
+            let job = self.job(path, &node);
+            self.request_run(vec![job], 1, false);
+        }
+        if let Some(validate) = toggle_validation {
+            self.set_validation(validate);
+        }
+        if toggle_dock {
+            self.config.output_dock = !self.config.output_dock;
+            if let Err(e) = self.config.save() {
+                self.error_message = format!("Failed to save settings: {}", e);
+            }
+        }
+
+        // Output dock, above the status bar
+        if self.config.output_dock {
+            let mut clear = false;
+            let mut hide = false;
+            egui::TopBottomPanel::bottom("output_dock")
+                .resizable(true)
+                .default_height(220.0)
+                .min_height(80.0)
+                .show(ctx, |ui| {
+                    ui.add_space(4.0);
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(format!("📜 {}", tr("Output")))
+                                .strong()
+                                .color(self.theme.on_surface));
+                        ui.label(egui::RichText::new(tr_fmt("The last {0} results are kept", &[&self.config.output_dock_max.max(1)]))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant));
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.small_button("✕").on_hover_text(tr("Hide the output dock")).clicked() {
+                                hide = true;
+                            }
+                            if ui.add_enabled(!self.output_dock.is_empty(), egui::Button::new(format!("🗑 {}", tr("Clear"))).small()).clicked() {
+                                clear = true;
+                            }
+                        });
+                    });
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .auto_shrink([false; 2])
+                        .stick_to_bottom(true)
+                        .show(ui, |ui| {
+                            if self.output_dock.is_empty() {
+                                ui.label(egui::RichText::new(tr("Results of the commands you run collect here, across tabs and folders"))
+                                        .color(self.theme.on_surface_variant));
+                            }
+                            for (id, section) in &mut self.output_dock {
+                                let color = if section.success { self.theme.success } else { self.theme.danger };
+                                egui::CollapsingHeader::new(egui::RichText::new(&section.title)
+                                        .color(color))
+                                    .id_salt(("output_dock", *id))
+                                    .default_open(!section.success)
+                                    .show(ui, |ui| {
+                                        if let Some(passed) = section.verified {
+                                            verification_badge(ui, &self.theme, passed);
+                                        }
+                                        output_view(ui, &section.output, &mut section.job, &self.theme, 4, self.collapse_repeats)
+                                    });
+                            }
+                        });
+                });
+            if clear {
+                self.output_dock.clear();
+            }
+            if hide {
+                self.config.output_dock = false;
+                if let Err(e) = self.config.save() {
+                    self.error_message = format!("Failed to save settings: {}", e);
+                }
+            }
+        }
+
+        // Modern sidebar
+        if self.config.sidebar == SidebarMode::Rail {
+            egui::SidePanel::left("sidebar_rail")
+                .exact_width(56.0)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.add_space(12.0);
+                    let mut tab_to_switch = None;
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        ui.vertical_centered(|ui| {
+                            for i in self.sidebar_order() {
+                                let tab = &self.tabs[i];
+                                if self.config.hidden_tabs.contains(&tab.name) {
+                                    continue;
+                                }
+                                let selected = i == self.current_tab_index;
+                                let response = ui.selectable_label(selected,
+                                    egui::RichText::new(tab_abbreviation(&tab.name))
+                                        .size(14.0)
+                                        .strong()
+                                        .color(if selected { egui::Color32::WHITE } else { self.theme.on_surface }))
+                                    .on_hover_text(&tab.name);
+                                if response.clicked() {
+                                    tab_to_switch = Some(i);
+                                }
+                                ui.add_space(4.0);
+                            }
+                        });
+                    });
+                    if let Some(tab_index) = tab_to_switch {
+                        self.switch_tab(tab_index);
+                    }
+                });
+        }
+        if self.config.sidebar == SidebarMode::Full {
+            egui::SidePanel::left("sidebar")
+                .min_width(280.0)
+                .max_width(350.0)
+                .show(ctx, |ui| {
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new(tr("Categories"))
+                            .size(18.0)
+                            .strong()
+                            .color(self.theme.on_surface));
+                    ui.add_space(8.0);
+
+                    let mut tab_to_switch = None;
+                    let order = self.sidebar_order();
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        for i in order {
+                            let tab = &self.tabs[i];
+                            if self.config.hidden_tabs.contains(&tab.name) {
+                                continue;
+                            }
+                            let selected = i == self.current_tab_index;
+                            
+                            let response = ui.selectable_label(selected, 
+                                egui::RichText::new(&tab.name)
+                                    .size(14.0)
+                                    .color(if selected { egui::Color32::WHITE } else { self.theme.on_surface }));
+                            
+                            if response.clicked() {
+                                tab_to_switch = Some(i);
+                            }
+                            
+                            ui.add_space(4.0);
+                        }
+                    });
+                    
+                    if let Some(tab_index) = tab_to_switch {
+                        self.switch_tab(tab_index);
+                    }
+                });
+        }
+
+        // Main content with modern grid layout
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.loading {
+                ui.centered_and_justified(|ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.spinner();
+                        ui.add_space(16.0);
+                        ui.label(egui::RichText::new("Loading system utilities...")
                                 .size(16.0)
+                                .color(self.theme.on_surface_variant));
+                    });
+                });
+                return;
+            }
+
+            let retry = if let Some(error) = &self.load_error {
+                self.render_state_message(ui, "⚠", "Failed to load utilities", error, "🔄 Retry")
+            } else if self.tabs.is_empty() {
+                self.render_state_message(ui, "📭", "No utilities available", "The tab data was loaded, but it does not contain any tabs", "🔄 Reload")
+            } else {
+                false
+            };
+            if retry {
+                self.reload_tabs();
+            }
+            if self.load_error.is_some() || self.tabs.is_empty() {
+                return;
+            }
+
+            let mut action = None;
+            let mut action_index = 0;
+            
+            egui::ScrollArea::vertical()
+                .auto_shrink([false; 2])
+                .show(ui, |ui| {
+                    ui.add_space(16.0);
+                    
+                    // Up directory card
+                    if !self.at_root() {
+                        egui::Frame::none()
+                            .fill(self.theme.surface_variant)
+                            .rounding(12.0)
+                            .inner_margin(16.0)
+                            .stroke(egui::Stroke::new(1.0, self.theme.border))
+                            .show(ui, |ui| {
+                                ui.horizontal(|ui| {
+                                    if ui.button("📁 .. Go Back").clicked() {
+                                        action = Some("go_back".to_string());
+                                    }
+                                });
+                            });
+                        ui.add_space(16.0);
+                    }
+
+                    // Modern grid layout, or a single column of rows in the compact density
+                    let compact = self.config.list_density == ListDensity::Compact;
+                    let cols = if compact { 1 } else { self.grid_columns(ui.available_width()) };
+                    let spacing = if compact { 4.0 } else { 16.0 };
+                    
+                    // Results of an "All tabs" search come sorted by tab, each tab under its own header
+                    let grouped = self.search_scope == SearchScope::AllTabs && !self.search_text.is_empty();
+                    let mut start = 0;
+                    while start < self.filtered.len() {
+                        let tab = self.current_items[self.filtered[start]].tab;
+                        let end = if grouped {
+                            self.filtered[start..].iter()
+                                .position(|&item| self.current_items[item].tab != tab)
+                                .map_or(self.filtered.len(), |len| start + len)
+                        } else {
+                            self.filtered.len()
+                        };
+
+                        let mut render_cards = |ui: &mut egui::Ui| {
+                            ui.columns(cols, |columns| {
+                                for (i, &item) in self.filtered.iter().enumerate().take(end).skip(start) {
+                                    let entry = &self.current_items[item];
+                                    let col = (i - start) % cols;
+                                    if let Some(entry_action) = self.render_category_card(&mut columns[col], entry, i) {
+                                        action = Some(entry_action);
+                                        action_index = i;
+                                    }
+                                    columns[col].add_space(spacing);
+                                }
+                            });
+                        };
+                        if grouped {
+                            egui::CollapsingHeader::new(egui::RichText::new(format!("{} ({})", self.tabs[tab].name, end - start))
+                                    .size(15.0)
+                                    .strong()
+                                    .color(self.theme.on_surface))
+                                .id_salt(("search_group", tab))
+                                .default_open(true)
+                                .show(ui, render_cards);
+                        } else {
+                            render_cards(ui);
+                        }
+                        start = end;
+                    }
+
+                    if self.filtered.is_empty() {
+                        ui.centered_and_justified(|ui| {
+                            ui.vertical_centered(|ui| {
+                                ui.label(egui::RichText::new("🔍")
+                                        .size(48.0)
+                                        .color(self.theme.on_surface_variant));
+                                ui.add_space(16.0);
+                                let message = if !self.search_text.is_empty() || self.tag_filter.is_some() {
+                                    "No utilities match your search"
+                                } else {
+                                    "This category is empty"
+                                };
+                                ui.label(egui::RichText::new(message)
+                                        .size(16.0)
+                                        .color(self.theme.on_surface_variant));
+                            });
+                        });
+                    }
+                });
+
+            // Handle actions
+            if let Some(action_type) = action {
+                match action_type.as_str() {
+                    "go_back" => self.go_back(),
+                    "enter" => {
+                        self.selected_index = action_index;
+                        self.enter_directory();
+                    }
+                    "execute" => {
+                        self.selected_index = action_index;
+                        self.execute_selected_command();
+                    }
+                    "run_folder" | "run_folder_recursive" => {
+                        if let Some(entry) = self.filtered_entry(action_index) {
+                            let (tab, id) = (entry.tab, entry.id);
+                            self.run_folder(tab, id, action_type == "run_folder_recursive");
+                        }
+                    }
+                    "preview" => {
+                        if let Some(entry) = self.filtered_entry(action_index) {
+                            let (path, node) = (entry.path.clone(), entry.node.clone());
+                            let contents = entry.is_directory.then(|| {
+                                self.tabs[entry.tab].tree.get(entry.id).into_iter()
+                                    .flat_map(|directory| directory.children())
+                                    .map(|child| {
+                                        let child = child.value();
+                                        if child.is_directory() { format!("{}/", child.name) } else { child.name.clone() }
+                                    })
+                                    .collect()
+                            });
+                            self.open_preview(path, &node, contents);
+                        }
+                    }
+                    "show_in_folder" => {
+                        let file = self.filtered_entry(action_index).and_then(|entry| match &entry.node.command {
+                            LinutilCommand::LocalFile { file, .. } => Some(absolute_script_args(&[], file).0),
+                            _ => None,
+                        });
+                        if let Some(file) = file {
+                            if let Err(e) = reveal_in_file_manager(&file) {
+                                self.error_message = format!("Failed to show {}: {}", file.display(), e);
+                            }
+                        }
+                    }
+                    "copy_command" => {
+                        if let Some(text) = self.filtered_entry(action_index).and_then(|entry| command_line(&entry.node.command)) {
+                            ui.output_mut(|o| o.copied_text = text);
+                            self.status_message = tr("Command copied to clipboard");
+                            self.toasts.push(Severity::Info, self.status_message.clone());
+                        }
+                    }
+                    "schedule" => {
+                        if let Some(entry) = self.filtered_entry(action_index) {
+                            self.schedule_draft = Some(ScheduleDraft {
+                                job: self.job(entry.path.clone(), &entry.node),
+                                typed_name: String::new(),
+                                at_time: false,
+                                delay_minutes: 30,
+                                hour: 3,
+                                minute: 0,
+                            });
+                        }
+                    }
+                    "multi_select" => {
+                        self.selected_index = action_index;
+                        self.toggle_multi_select();
+                        self.multi_select = true;
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        // Modern command output window
+        if self.show_command_output {
+            egui::Window::new("Command Output")
+                .default_width(700.0)
+                .default_height(500.0)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(&self.command_output_timing)
+                                .color(self.theme.on_surface_variant));
+                        if let Some(passed) = self.command_output_verified.filter(|_| self.command_output_batch.is_none()) {
+                            verification_badge(ui, &self.theme, passed);
+                        }
+                    });
+                    ui.add_space(8.0);
+
+                    egui::ScrollArea::vertical()
+                        .stick_to_bottom(self.auto_scroll_output)
+                        .show(ui, |ui| {
+                        let Some(batch) = &mut self.command_output_batch else {
+                            output_view(ui, &self.command_output, &mut self.command_output_job, &self.theme, 20, self.collapse_repeats);
+                            return;
+                        };
+
+                        ui.label(egui::RichText::new(&batch.summary)
+                                .strong()
+                                .color(self.theme.on_surface));
+                        ui.add_space(8.0);
+                        // Failures start expanded, so they are what catches the eye
+                        for (i, section) in batch.sections.iter_mut().enumerate() {
+                            let color = if section.success { self.theme.success } else { self.theme.danger };
+                            egui::CollapsingHeader::new(egui::RichText::new(&section.title)
+                                    .color(color))
+                                .id_salt(("output_section", batch.id, i))
+                                .default_open(!section.success)
+                                .show(ui, |ui| {
+                                    if let Some(passed) = section.verified {
+                                        verification_badge(ui, &self.theme, passed);
+                                    }
+                                    output_view(ui, &section.output, &mut section.job, &self.theme, 4, self.collapse_repeats)
+                                });
+                        }
+                        if !batch.skipped.is_empty() {
+                            ui.add_space(8.0);
+                            ui.label(egui::RichText::new(format!("⏭ {}: {}", batch.skipped_heading, batch.skipped.join(", ")))
+                                    .color(self.theme.on_surface_variant));
+                        }
+                    });
+                    
+                    ui.add_space(12.0);
+                    ui.horizontal(|ui| {
+                        if ui.button("📋 Copy").clicked() {
+                            ui.output_mut(|o| o.copied_text = self.command_output.text.clone());
+                            self.status_message = tr("Output copied to clipboard");
+                            self.toasts.push(Severity::Info, self.status_message.clone());
+                        }
+
+                        if ui.button("🐞 Copy diagnostic report")
+                            .on_hover_text("This output with the version, system and settings, for a bug report")
+                            .clicked() {
+                            self.copy_diagnostic_report(ctx);
+                        }
+
+                        ui.checkbox(&mut self.auto_scroll_output, "📌 Auto-scroll")
+                            .on_hover_text("Follow new output as it arrives");
+
+                        if ui.checkbox(&mut self.collapse_repeats, "≡ Collapse repeats")
+                            .on_hover_text("Show runs of identical lines once, like uniq -c. Copying still gives every line")
+                            .changed()
+                        {
+                            self.command_output_job = None;
+                            for section in self.command_output_batch.iter_mut().flat_map(|batch| &mut batch.sections) {
+                                section.job = None;
+                            }
+                            for (_, section) in &mut self.output_dock {
+                                section.job = None;
+                            }
+                        }
+
+                        if self.command_output.text != self.command_output_plain
+                            && ui.button("📋 Copy without timestamps").clicked()
+                        {
+                            ui.output_mut(|o| o.copied_text = self.command_output_plain.clone());
+                            self.status_message = tr("Output copied to clipboard");
+                            self.toasts.push(Severity::Info, self.status_message.clone());
+                        }
+                        
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if ui.button("✕ Close").clicked() {
+                                self.show_command_output = false;
+                            }
+                        });
+                    });
+                });
+        }
+
+        // Script preview window
+        let mut preview_open = self.preview.is_some();
+        let mut env_changed = false;
+        let mut notes_changed = false;
+        if let Some(preview) = &mut self.preview {
+            egui::Window::new("📋 Command Preview")
+                .open(&mut preview_open)
+                .default_width(700.0)
+                .default_height(500.0)
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new(&preview.name)
+                            .size(16.0)
+                            .strong()
+                            .color(self.theme.on_surface));
+                    if !preview.description.is_empty() {
+                        if self.config.markdown_descriptions {
+                            ui.label(markdown::render(&preview.description, &self.theme, 14.0, self.theme.on_surface_variant));
+                        } else {
+                            ui.label(egui::RichText::new(&preview.description)
+                                    .color(self.theme.on_surface_variant));
+                        }
+                    }
+                    if !preview.task_list.is_empty() {
+                        ui.label(egui::RichText::new(format!("Task List: {}", preview.task_list))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant));
+                    }
+
+                    if let Some(command_line) = &preview.command_line {
+                        ui.add_space(4.0);
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new(command_line)
+                                    .monospace()
+                                    .color(self.theme.on_surface_variant));
+                            if ui.button("📋 Copy Command").clicked() {
+                                ui.output_mut(|o| o.copied_text = command_line.clone());
+                                self.status_message = tr("Command copied to clipboard");
+                                self.toasts.push(Severity::Info, self.status_message.clone());
+                            }
+                            if ui.button("{ } Copy as JSON")
+                                .on_hover_text("The full definition of the command, e.g. for bug reports")
+                                .clicked() {
+                                ui.output_mut(|o| o.copied_text = preview.json.clone());
+                                self.status_message = tr("Command definition copied to clipboard");
+                                self.toasts.push(Severity::Info, self.status_message.clone());
+                            }
+                        });
+                    }
+
+                    if let Some(working_dir) = &preview.working_dir {
+                        ui.label(egui::RichText::new(format!("📁 Runs in {}", working_dir.display()))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant));
+                    }
+
+                    if let Some((interpreter, from_shebang)) = &preview.interpreter {
+                        let source = if *from_shebang { "from the shebang" } else { "no shebang, so /bin/sh -e" };
+                        ui.label(egui::RichText::new(format!("⚙ Interpreter: {} ({})", interpreter, source))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant));
+                    }
+
+                    if let Some(verify) = &preview.verify {
+                        ui.label(egui::RichText::new(format!("🔎 Verified afterwards with: {}", verify))
+                                .size(12.0)
+                                .color(self.theme.on_surface_variant));
+                    }
+
+                    if let Some(script) = &preview.script {
+                        if ui.button("📂 Show in folder")
+                            .on_hover_text(script.display().to_string())
+                            .clicked() {
+                            match reveal_in_file_manager(script) {
+                                Ok(()) => self.status_message = tr_fmt("Opened the folder of {0}", &[&preview.name]),
+                                Err(e) => self.error_message = format!("Failed to show {}: {}", script.display(), e),
+                            }
+                        }
+                    }
+
+                    if !preview.dependencies.is_empty() {
+                        ui.add_space(4.0);
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label(egui::RichText::new("Requires:")
+                                    .color(self.theme.on_surface_variant));
+                            for (dependency, installed) in &preview.dependencies {
+                                let (icon, color, hint) = if *installed {
+                                    ("✔", self.theme.success, "Installed")
+                                } else {
+                                    ("✘", self.theme.danger, "Not found on PATH")
+                                };
+                                ui.label(egui::RichText::new(format!("{} {}", icon, dependency))
+                                        .monospace()
+                                        .color(color))
+                                    .on_hover_text(hint);
+                            }
+                        });
+                    }
+
+                    let mut note = self.notes.remove(&preview.path).unwrap_or_default();
+                    let notes_header = if note.is_empty() { "Notes".to_string() } else { "Notes 📝".to_string() };
+                    egui::CollapsingHeader::new(notes_header)
+                        .id_salt("preview_notes")
+                        .default_open(!note.is_empty())
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new("Only stored on this machine, and searched along with the name and description")
+                                    .size(12.0)
+                                    .color(self.theme.on_surface_variant));
+                            notes_changed = ui.add(egui::TextEdit::multiline(&mut note)
+                                    .hint_text("Why you run it, what to watch out for, ...")
+                                    .desired_rows(3)
+                                    .desired_width(f32::INFINITY))
+                                .changed();
+                        });
+                    if !note.is_empty() {
+                        self.notes.insert(preview.path.clone(), note);
+                    }
+
+                    // Nothing of a directory runs, so there is no environment or source to show
+                    if let Some(contents) = &preview.contents {
+                        ui.add_space(8.0);
+                        ui.label(egui::RichText::new(tr_fmt("Contains {0} entries", &[&contents.len()]))
                                 .strong()
                                 .color(self.theme.on_surface));
-                                
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if !entry.node.task_list.is_empty() {
-                                ui.label(egui::RichText::new(&entry.node.task_list)
-                                        .size(10.0)
-                                        .background_color(self.theme.secondary.gamma_multiply(0.3))
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            let icons = self.config.icon_theme;
+                            for name in contents {
+                                let icon = if name.ends_with('/') { icons.dir_icon() } else { icons.cmd_icon() };
+                                ui.label(egui::RichText::new(format!("{} {}", icon, name))
                                         .color(self.theme.on_surface_variant));
                             }
                         });
-                    });
+                        return;
+                    }
+
+                    let mut env = self.config.command_env.remove(&preview.path).unwrap_or_default();
+                    let env_header = if env.is_empty() {
+                        "Environment".to_string()
+                    } else {
+                        format!("Environment ({})", env.len())
+                    };
+                    egui::CollapsingHeader::new(env_header)
+                        .id_salt("preview_env")
+                        .show(ui, |ui| {
+                            ui.label(egui::RichText::new("Set only for this command, on top of the global variables from the settings")
+                                    .size(12.0)
+                                    .color(self.theme.on_surface_variant));
+                            env_changed = edit_env(ui, &mut env, &mut preview.env_draft);
+                        });
+                    if !env.is_empty() {
+                        self.config.command_env.insert(preview.path.clone(), env);
+                    }
 
                     ui.add_space(8.0);
+                    let too_large = preview.source.len() > highlight::MAX_HIGHLIGHT_BYTES;
+                    ui.checkbox(&mut self.highlight_preview, "Syntax highlighting")
+                        .on_hover_text(if too_large {
+                            "This script is large; highlighting may slow down the preview"
+                        } else if preview.interpreter.as_ref().is_some_and(|(name, _)| !highlight::is_shell(name)) {
+                            "Only shell syntax is known, so other languages are colored as if they were shell"
+                        } else {
+                            "Color comments, strings, keywords and variables"
+                        });
+                    ui.add_space(8.0);
 
-                    // Description
-                    if !entry.node.description.is_empty() {
-                        ui.label(egui::RichText::new(&entry.node.description)
-                                .size(13.0)
-                                .color(self.theme.on_surface_variant));
-                        ui.add_space(12.0);
+                    let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+                    if self.highlight_preview && preview.highlighted.is_none() {
+                        preview.highlighted = Some(highlight::highlight_shell(&preview.source, &self.theme, font_id));
                     }
 
-                    // Action buttons
-                    ui.horizontal(|ui| {
-                        if entry.has_children {
-                            if self.render_modern_button(ui, "Open", "📂", self.theme.primary).clicked() {
-                                action = Some("enter".to_string());
+                    egui::ScrollArea::vertical().show(ui, |ui| {
+                        let mut source = preview.source.as_str();
+                        let text_edit = egui::TextEdit::multiline(&mut source)
+                            .font(egui::TextStyle::Monospace)
+                            .desired_rows(20)
+                            .desired_width(f32::INFINITY);
+
+                        match preview.highlighted.as_ref().filter(|_| self.highlight_preview) {
+                            Some(job) => {
+                                let mut layouter = |ui: &egui::Ui, _text: &str, wrap_width: f32| {
+                                    let mut job = job.clone();
+                                    job.wrap.max_width = wrap_width;
+                                    ui.fonts(|fonts| fonts.layout_job(job))
+                                };
+                                ui.add(text_edit.layouter(&mut layouter));
                             }
-                        } else {
-                            if self.render_modern_button(ui, "Execute", "▶️", self.theme.success).clicked() {
-                                action = Some("execute".to_string());
+                            None => {
+                                ui.add(text_edit);
                             }
-                            
-                            ui.add_space(8.0);
-                            
-                            if self.render_modern_button(ui, "Preview", "👁️", self.theme.secondary).clicked() {
-                                action = Some("preview".to_string());
+                        }
+                    });
+                });
+        }
+        if !preview_open {
+            self.preview = None;
+        }
+        if env_changed {
+            if let Err(e) = self.config.save() {
+                self.error_message = format!("Failed to save settings: {}", e);
+            }
+        }
+        if notes_changed {
+            if let Err(e) = notes::save(&self.notes) {
+                self.error_message = format!("Failed to save notes: {}", e);
+            }
+        }
+
+        self.scroll_to_selected = false;
+
+        // Command palette
+        if let Some(palette) = &mut self.palette {
+            let mut chosen = None;
+            // The query field keeps the focus, so the list is driven from here
+            let (run, enter, down, up) = ctx.input_mut(|input| (
+                input.consume_key(egui::Modifiers::COMMAND, egui::Key::Enter),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            ));
+            if down && palette.selected + 1 < palette.match_count() {
+                palette.selected += 1;
+            }
+            if up {
+                palette.selected = palette.selected.saturating_sub(1);
+            }
+            if run || enter {
+                chosen = palette.selected_path().map(|path| (path.to_string(), run));
+            }
+
+            egui::Window::new("🔎 Go to Command")
+                .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+                .collapsible(false)
+                .resizable(false)
+                .default_width(560.0)
+                .show(ctx, |ui| {
+                    let response = ui.add(egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text(tr("Type to search all commands"))
+                        .desired_width(f32::INFINITY));
+                    response.request_focus();
+                    if response.changed() {
+                        palette.update_matches();
+                    }
+                    ui.add_space(4.0);
+
+                    egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                        for (i, path) in palette.matches().enumerate() {
+                            let item = ui.selectable_label(i == palette.selected, path);
+                            if i == palette.selected && (up || down) {
+                                item.scroll_to_me(None);
                             }
-                            
-                            if entry.node.multi_select {
-                                ui.add_space(8.0);
-                                let multi_text = if is_multi_selected { "Deselect" } else { "Select" };
-                                if self.render_modern_button(ui, multi_text, "☑️", self.theme.accent).clicked() {
-                                    action = Some("multi_select".to_string());
-                                }
+                            if item.clicked() {
+                                chosen = Some((path.to_string(), false));
                             }
                         }
+                        if palette.match_count() == 0 {
+                            ui.label(egui::RichText::new(tr("No matching commands"))
+                                    .color(self.theme.on_surface_variant));
+                        }
                     });
-                })
-            })
-            .response;
-        
-        // Add hover effect to entire card
-        if response.hovered() {
-            ui.ctx().set_cursor_icon(egui::CursorIcon::PointingHand);
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(tr_fmt("Enter: go to · {0}: run · Esc: close", &[
+                        &ctx.format_shortcut(&egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Enter)),
+                    ]))
+                            .size(12.0)
+                            .color(self.theme.on_surface_variant));
+                });
+
+            if let Some((path, run)) = chosen {
+                self.palette = None;
+                match self.reveal_command(&path) {
+                    Some(node) if run => {
+                        let job = self.job(path, &node);
+                        self.request_run(vec![job], 1, false);
+                    }
+                    Some(_) => {}
+                    None => self.status_message = tr_fmt("{0} is no longer available", &[&path]),
+                }
+            }
         }
 
-        action
-    }
-}
+        // Tab switcher
+        if let Some(switcher) = &mut self.tab_switcher {
+            let mut chosen = None;
+            let (enter, down, up) = ctx.input_mut(|input| (
+                input.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                input.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            ));
+            if down && switcher.selected + 1 < switcher.match_count() {
+                switcher.selected += 1;
+            }
+            if up {
+                switcher.selected = switcher.selected.saturating_sub(1);
+            }
+            if enter {
+                chosen = switcher.selected_path().map(str::to_string);
+            }
 
-impl eframe::App for LinutilApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Check for command execution results
-        self.check_command_result();
+            egui::Window::new("🗂 Switch Tab")
+                .anchor(egui::Align2::CENTER_TOP, egui::vec2(0.0, 80.0))
+                .collapsible(false)
+                .resizable(false)
+                .default_width(360.0)
+                .show(ctx, |ui| {
+                    let response = ui.add(egui::TextEdit::singleline(&mut switcher.query)
+                        .hint_text(tr("Type a tab name"))
+                        .desired_width(f32::INFINITY));
+                    response.request_focus();
+                    if response.changed() {
+                        switcher.update_matches();
+                    }
+                    ui.add_space(4.0);
 
-        // Force repaint for loading states
-        if self.loading || self.executing_command {
-            ctx.request_repaint();
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for (i, name) in switcher.matches().enumerate() {
+                            let item = ui.selectable_label(i == switcher.selected, name);
+                            if i == switcher.selected && (up || down) {
+                                item.scroll_to_me(None);
+                            }
+                            if item.clicked() {
+                                chosen = Some(name.to_string());
+                            }
+                        }
+                        if switcher.match_count() == 0 {
+                            ui.label(egui::RichText::new(tr("No matching tabs"))
+                                    .color(self.theme.on_surface_variant));
+                        }
+                    });
+                    ui.add_space(4.0);
+                    ui.label(egui::RichText::new(tr("Enter: switch · Esc: close"))
+                            .size(12.0)
+                            .color(self.theme.on_surface_variant));
+                });
+
+            if let Some(name) = chosen {
+                self.tab_switcher = None;
+                match self.tabs.iter().position(|tab| tab.name == name) {
+                    Some(tab_index) => self.switch_tab(tab_index),
+                    None => self.status_message = tr_fmt("{0} is no longer available", &[&name]),
+                }
+            }
         }
 
-        // Modern top bar
-        egui::TopBottomPanel::top("top_panel")
-            .min_height(64.0)
-            .show(ctx, |ui| {
-                ui.add_space(8.0);
-                ui.horizontal(|ui| {
-                    // Logo and title
-                    ui.label(egui::RichText::new("🐧 Linutil")
-                            .size(24.0)
+        // Keyboard shortcuts overlay
+        if self.show_help {
+            let response = egui::Window::new("⌨ Keyboard Shortcuts")
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let mut category = "";
+                    for (shortcut, bindings) in self.keymap.shortcuts() {
+                        if shortcut.category != category {
+                            category = shortcut.category;
+                            ui.add_space(8.0);
+                            ui.label(egui::RichText::new(category)
+                                    .strong()
+                                    .color(self.theme.primary));
+                        }
+                        ui.horizontal(|ui| {
+                            ui.add_sized([140.0, 18.0], egui::Label::new(
+                                egui::RichText::new(shortcuts::format_bindings(ctx, bindings))
+                                    .monospace()
+                                    .color(self.theme.on_surface)));
+                            ui.label(egui::RichText::new(shortcut.description)
+                                    .color(self.theme.on_surface_variant));
+                        });
+                    }
+                });
+
+            // Clicking anywhere outside of the overlay closes it
+            let clicked_outside = ctx.input(|i| {
+                i.pointer.any_pressed() && i.pointer.interact_pos().is_some_and(|pos| {
+                    response.as_ref().is_some_and(|r| !r.response.rect.contains(pos))
+                })
+            });
+            if clicked_outside {
+                self.show_help = false;
+            }
+        }
+
+        // Settings window
+        if self.show_settings {
+            let mut changed = false;
+            let mut validation_changed = false;
+            let mut theme_changed = false;
+            let mut rescan = false;
+            let mut copy_report = false;
+            let mut tab_order = self.sidebar_order();
+            let languages = i18n::available_languages();
+            egui::Window::new("⚙ Settings")
+                .open(&mut self.show_settings)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new("Output")
                             .strong()
-                            .color(self.theme.primary));
-                    ui.label(egui::RichText::new("System Management Suite")
-                            .size(16.0)
+                            .color(self.theme.on_surface));
+                    changed |= ui.checkbox(&mut self.config.timestamp_output, "Timestamp each output line")
+                        .on_hover_text("Prefix lines with [mm:ss.mmm] measured from command start")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label("Keep the last");
+                        changed |= ui.add(egui::DragValue::new(&mut self.config.max_output_mb).range(1..=512).suffix(" MB"))
+                            .on_hover_text("Older lines of commands with more output are dropped, so they can't freeze the app")
+                            .changed();
+                        ui.label("of output per command");
+                    });
+                    changed |= ui.checkbox(&mut self.config.save_full_output, "Save the full output to a file when it is longer")
+                        .on_hover_text("Written to the output folder in the data directory")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        changed |= ui.checkbox(&mut self.config.auto_close_output, "Don't open the output when everything succeeded")
+                            .on_hover_text("Failures still open it. The status bar says so instead, with a button to open it anyway")
+                            .changed();
+                        changed |= ui.add_enabled(self.config.auto_close_output, egui::DragValue::new(&mut self.config.toast_secs).range(1..=60).suffix(" s"))
+                            .on_hover_text("How long the status bar shows the notice")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        changed |= ui.checkbox(&mut self.config.output_dock, "Collect results in an output dock, keeping the last")
+                            .on_hover_text("Instead of opening the output window after each run. Results are kept across tabs and folders")
+                            .changed();
+                        if ui.add(egui::DragValue::new(&mut self.config.output_dock_max).range(1..=200)).changed() {
+                            let excess = self.output_dock.len().saturating_sub(self.config.output_dock_max.max(1));
+                            self.output_dock.drain(..excess);
+                            changed = true;
+                        }
+                    });
+                    changed |= ui.checkbox(&mut self.config.show_toasts, "Show notifications in the corner")
+                        .on_hover_text("When commands start and finish, or something is copied. Click a finished run's to see its output")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label("Output that is not valid UTF-8");
+                        egui::ComboBox::from_id_salt("output_encoding")
+                            .selected_text(self.config.output_encoding.label())
+                            .show_ui(ui, |ui| {
+                                for encoding in OutputEncoding::ALL {
+                                    changed |= ui.selectable_value(&mut self.config.output_encoding, encoding, encoding.label()).changed();
+                                }
+                            });
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Show");
+                        egui::ComboBox::from_id_salt("output_streams")
+                            .selected_text(self.config.output_streams.label())
+                            .show_ui(ui, |ui| {
+                                for streams in OutputStreams::ALL {
+                                    changed |= ui.selectable_value(&mut self.config.output_streams, streams, streams.label()).changed();
+                                }
+                            });
+                    });
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Appearance")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    egui::ComboBox::from_id_salt("theme_variant")
+                        .selected_text(self.config.theme.label())
+                        .show_ui(ui, |ui| {
+                            for variant in ThemeVariant::ALL {
+                                theme_changed |= ui.selectable_value(&mut self.config.theme, variant, variant.label()).changed();
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        ui.label("Accent color");
+                        let mut color = self.theme.primary;
+                        if ui.color_edit_button_srgba(&mut color).changed() {
+                            self.config.accent_color = Some([color.r(), color.g(), color.b()]);
+                            theme_changed = true;
+                        }
+                        if ui.add_enabled(self.config.accent_color.is_some(), egui::Button::new("Reset to default")).clicked() {
+                            self.config.accent_color = None;
+                            theme_changed = true;
+                        }
+                    });
+                    theme_changed |= ui.checkbox(&mut self.config.light_mode, "Light mode").changed();
+                    egui::ComboBox::from_id_salt("icon_theme")
+                        .selected_text(self.config.icon_theme.label())
+                        .show_ui(ui, |ui| {
+                            for icons in IconTheme::ALL {
+                                changed |= ui.selectable_value(&mut self.config.icon_theme, icons, icons.label()).changed();
+                            }
+                        });
+                    egui::ComboBox::from_id_salt("list_density")
+                        .selected_text(self.config.list_density.label())
+                        .show_ui(ui, |ui| {
+                            for density in ListDensity::ALL {
+                                changed |= ui.selectable_value(&mut self.config.list_density, density, density.label()).changed();
+                            }
+                        });
+                    changed |= theme_changed;
+                    changed |= ui.checkbox(&mut self.config.markdown_descriptions, "Format descriptions")
+                        .on_hover_text("Show **bold**, `code` and bullet lists in descriptions as such, instead of the raw text")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.config.size_bypass, "Allow small windows")
+                        .on_hover_text(format!("Lay the app out even below {}×{}, where it gets cramped", MIN_WINDOW_SIZE.x, MIN_WINDOW_SIZE.y))
+                        .changed();
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Language")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    let system_default = "System default".to_string();
+                    egui::ComboBox::from_id_salt("language")
+                        .selected_text(if self.config.language.is_empty() { &system_default } else { &self.config.language })
+                        .show_ui(ui, |ui| {
+                            changed |= ui.selectable_value(&mut self.config.language, String::new(), &system_default).changed();
+                            for language in &languages {
+                                changed |= ui.selectable_value(&mut self.config.language, language.clone(), language).changed();
+                            }
+                        })
+                        .response
+                        .on_hover_text("Translations are read from ~/.config/linutil/locales and apply after a restart");
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Keyboard")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    let mut keymap_changed = false;
+                    egui::ComboBox::from_id_salt("keymap_preset")
+                        .selected_text(self.config.keymap_preset.label())
+                        .show_ui(ui, |ui| {
+                            for preset in KeymapPreset::ALL {
+                                keymap_changed |= ui.selectable_value(&mut self.config.keymap_preset, preset, preset.label()).changed();
+                            }
+                        });
+                    if keymap_changed {
+                        self.keymap = shortcuts::Keymap::new(self.config.keymap_preset, &self.config.keybindings);
+                        changed = true;
+                    }
+                    ui.label(egui::RichText::new(format!("Single keys can be changed under \"keybindings\" in {}, e.g. \"select_next\": [\"J\", \"ArrowDown\"]",
+                                                         AppConfig::path().map_or_else(|| "the config file".to_string(), |path| path.display().to_string())))
+                            .size(12.0)
                             .color(self.theme.on_surface_variant));
-                    
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        // Toggle sidebar button
-                        if ui.button(if self.show_sidebar { "◀" } else { "▶" }).clicked() {
-                            self.show_sidebar = !self.show_sidebar;
+                    for warning in &self.keymap.warnings {
+                        ui.label(egui::RichText::new(format!("⚠ {}", warning))
+                                .size(12.0)
+                                .color(self.theme.warning));
+                    }
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Compatibility")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    // Both reload the tabs, which deletes the scripts of running commands
+                    ui.add_enabled_ui(!self.executing_command, |ui| {
+                        ui.horizontal(|ui| {
+                            validation_changed = ui.checkbox(&mut self.config.validate, "Hide commands incompatible with this system")
+                                .changed();
+                            if ui.button("🔄 Re-scan").on_hover_text("Check the system again, e.g. after installing a package manager").clicked() {
+                                rescan = true;
+                            }
+                        });
+                    });
+                    changed |= validation_changed;
+                    let found: Vec<&str> = self.package_managers.iter().map(|manager| manager.binary()).collect();
+                    ui.label(egui::RichText::new(format!("Package managers found: {}", if found.is_empty() { "none".to_string() } else { found.join(", ") }))
+                            .size(12.0)
+                            .color(self.theme.on_surface_variant));
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Execution")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    changed |= ui.checkbox(&mut self.config.safe_mode, "Safe mode")
+                        .on_hover_text("Browse and preview commands without being able to run any of them")
+                        .changed();
+                    changed |= ui.checkbox(&mut self.config.confirm_close, "Ask before closing while commands run")
+                        .on_hover_text("Closing the window stops running commands and skips the rest of the queue")
+                        .changed();
+                    ui.horizontal(|ui| {
+                        ui.label("Start over after");
+                        changed |= ui.add(egui::DragValue::new(&mut self.config.idle_reset_mins).range(0..=1440).suffix(" min"))
+                            .changed();
+                        ui.label("without input");
+                    }).response.on_hover_text("Return to the first tab and clear the selection, search and open windows, e.g. on shared terminals. 0 turns it off");
+                    changed |= ui.checkbox(&mut self.config.skip_confirmation, "Run commands without confirmation")
+                        .on_hover_text("Skip the dialog that shows the exact command before it runs")
+                        .changed();
+                    if !self.config.skip_confirm_for.is_empty() {
+                        let mut removed = None;
+                        egui::CollapsingHeader::new(format!("Commands that never ask ({})", self.config.skip_confirm_for.len()))
+                            .id_salt("skip_confirm_for")
+                            .show(ui, |ui| {
+                                for path in &self.config.skip_confirm_for {
+                                    ui.horizontal(|ui| {
+                                        if ui.small_button("✖").on_hover_text("Ask again before running it").clicked() {
+                                            removed = Some(path.clone());
+                                        }
+                                        ui.label(path);
+                                    });
+                                }
+                                if ui.button("Ask for all of them again").clicked() {
+                                    self.config.skip_confirm_for.clear();
+                                    changed = true;
+                                }
+                            });
+                        if let Some(path) = removed {
+                            self.config.skip_confirm_for.remove(&path);
+                            changed = true;
+                        }
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("When a command of a batch fails");
+                        changed |= ui.radio_value(&mut self.config.stop_on_failure, false, "Continue").changed();
+                        changed |= ui.radio_value(&mut self.config.stop_on_failure, true, "Stop")
+                            .on_hover_text("Skip the rest of the batch, for batches where later steps depend on earlier ones")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        changed |= ui.checkbox(&mut self.config.disk_space_check, "Warn before installs when less than")
+                            .on_hover_text("Shows the free space of / in the confirmation dialog of install commands")
+                            .changed();
+                        changed |= ui.add_enabled(self.config.disk_space_check, egui::DragValue::new(&mut self.config.min_free_space_mb).range(0..=1_000_000).suffix(" MB"))
+                            .changed();
+                        ui.label("is free");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Retry failed downloads and installs");
+                        changed |= ui.add(egui::DragValue::new(&mut self.config.retry_attempts).range(0..=10).suffix("×"))
+                            .on_hover_text("Commands using curl, wget or a package manager are run again when they fail. 0 turns this off")
+                            .changed();
+                        ui.label("first after");
+                        changed |= ui.add_enabled(self.config.retry_attempts > 0, egui::DragValue::new(&mut self.config.retry_delay_secs).range(1..=600).suffix(" s"))
+                            .on_hover_text("Each further retry waits twice as long")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Working directory of inline commands");
+                        changed |= ui.add(egui::TextEdit::singleline(&mut self.config.command_dir)
+                                .hint_text("Directory linutil was started in"))
+                            .on_hover_text("Scripts always run from their own directory")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Shell of inline commands");
+                        changed |= ui.add(egui::TextEdit::singleline(&mut self.config.shell).hint_text("sh"))
+                            .on_hover_text("e.g. bash, for commands using its features. Scripts name their own interpreter")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Kill commands running longer than");
+                        changed |= ui.add(egui::DragValue::new(&mut self.config.command_timeout_secs).range(0..=86_400).suffix(" s"))
+                            .on_hover_text("0 lets commands run for as long as they take")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Command log");
+                        let default_log = exec_log::default_path()
+                            .map_or_else(|| "No data directory found".to_string(), |path| path.display().to_string());
+                        changed |= ui.add(egui::TextEdit::singleline(&mut self.config.exec_log)
+                                .hint_text(default_log))
+                            .on_hover_text("Every executed command is appended with its exit code and duration")
+                            .changed();
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("Your scripts");
+                        let default_dir = config::data_dir()
+                            .map_or_else(|| "No data directory found".to_string(), |dir| dir.join("scripts").display().to_string());
+                        changed |= ui.add(egui::TextEdit::singleline(&mut self.config.scripts_dir)
+                                .hint_text(default_dir))
+                            .on_hover_text(format!("Executable files here are the commands of the \"{}\" tab, described by a \"# Description:\" comment. Reload to pick up changes", SCRIPTS_TAB))
+                            .changed();
+                    });
+                    for problem in &self.script_problems {
+                        ui.label(egui::RichText::new(format!("⚠ {}", problem))
+                                .size(12.0)
+                                .color(self.theme.warning));
+                    }
+                    if let Some(dir) = self.config.command_dir().filter(|dir| !dir.is_dir()) {
+                        ui.label(egui::RichText::new(format!("⚠ {} is not a directory, inline commands will fail", dir.display()))
+                                .size(12.0)
+                                .color(self.theme.warning));
+                    }
+                    ui.horizontal(|ui| {
+                        ui.label("Parallel commands for Execute All");
+                        changed |= ui.add(egui::DragValue::new(&mut self.config.max_parallel).range(1..=16))
+                            .on_hover_text("1 runs the batch serially, in selection order")
+                            .changed();
+                    });
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Environment")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    ui.label(egui::RichText::new("Set for every command. Variables of a single command, set in its preview, take precedence.")
+                            .size(12.0)
+                            .color(self.theme.on_surface_variant));
+                    changed |= edit_env(ui, &mut self.config.env, &mut self.env_draft);
+
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new("Profiles are named sets of variables, e.g. for work and home, switched from the top bar. The active one takes precedence over the variables above.")
+                            .size(12.0)
+                            .color(self.theme.on_surface_variant));
+                    ui.horizontal(|ui| {
+                        let selected = if self.config.env_profile.is_empty() { "No profile" } else { self.config.env_profile.as_str() };
+                        egui::ComboBox::from_id_salt("env_profile")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                changed |= ui.selectable_value(&mut self.config.env_profile, String::new(), "No profile").changed();
+                                for name in self.config.env_profiles.keys() {
+                                    changed |= ui.selectable_value(&mut self.config.env_profile, name.clone(), name).changed();
+                                }
+                            });
+                        if !self.config.env_profile.is_empty() && ui.button("🗑 Delete").clicked() {
+                            self.config.env_profiles.remove(&self.config.env_profile);
+                            self.config.env_profile.clear();
+                            changed = true;
                         }
-                        
-                        ui.add_space(16.0);
-                        
-                        // Execution status
-                        if self.executing_command {
-                            ui.spinner();
-                            ui.label(egui::RichText::new("Executing...")
-                                    .color(self.theme.warning));
+                        ui.separator();
+                        ui.add(egui::TextEdit::singleline(&mut self.profile_name_draft)
+                            .hint_text("New profile")
+                            .desired_width(120.0));
+                        let name = self.profile_name_draft.trim();
+                        if ui.add_enabled(!name.is_empty() && !self.config.env_profiles.contains_key(name), egui::Button::new("➕ Add"))
+                            .clicked() {
+                            self.config.env_profiles.insert(name.to_string(), BTreeMap::new());
+                            self.config.env_profile = name.to_string();
+                            self.profile_name_draft.clear();
+                            changed = true;
                         }
-                        
-                        // Multi-select indicator
-                        if !self.selected_commands.is_empty() {
-                            ui.label(egui::RichText::new(format!("{} selected", self.selected_commands.len()))
-                                    .background_color(self.theme.accent.gamma_multiply(0.3))
-                                    .color(self.theme.on_surface));
-                            
-                            if ui.button("Execute All").clicked() {
-                                for cmd in &self.selected_commands {
-                                    if let Some(tx) = &self.command_tx {
-                                        let tab_name = self.tabs[self.current_tab_index].name.clone();
-                                        let _ = tx.send((tab_name, cmd.clone()));
-                                    }
+                    });
+                    if let Some(vars) = self.config.env_profiles.get_mut(&self.config.env_profile) {
+                        changed |= edit_env(ui, vars, &mut self.profile_env_draft);
+                    }
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Tabs")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    let mut swap = None;
+                    for (position, &i) in tab_order.iter().enumerate() {
+                        let name = &self.tabs[i].name;
+                        ui.horizontal(|ui| {
+                            let mut visible = !self.config.hidden_tabs.contains(name);
+                            if ui.checkbox(&mut visible, name).changed() {
+                                if visible {
+                                    self.config.hidden_tabs.remove(name);
+                                } else {
+                                    self.config.hidden_tabs.insert(name.clone());
                                 }
-                                self.selected_commands.clear();
-                                self.multi_select = false;
+                                changed = true;
                             }
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                if ui.add_enabled(position + 1 < tab_order.len(), egui::Button::new("⬇")).clicked() {
+                                    swap = Some((position, position + 1));
+                                }
+                                if ui.add_enabled(position > 0, egui::Button::new("⬆")).clicked() {
+                                    swap = Some((position - 1, position));
+                                }
+                            });
+                        });
+                    }
+                    if let Some((a, b)) = swap {
+                        tab_order.swap(a, b);
+                        self.config.tab_order = tab_order.iter().map(|&i| self.tabs[i].name.clone()).collect();
+                        changed = true;
+                    }
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Diagnostics")
+                            .strong()
+                            .color(self.theme.on_surface));
+                    ui.horizontal(|ui| {
+                        if ui.button("🩺 Run self-test")
+                            .on_hover_text("Check the shell, tab data, config, data directory and tools commands rely on")
+                            .clicked() {
+                            self.self_test = Some(selftest::run(&self.config));
+                        }
+                        if ui.button("🐞 Copy diagnostic report")
+                            .on_hover_text("Version, system, self-test, settings and the last run, for a bug report")
+                            .clicked() {
+                            copy_report = true;
                         }
                     });
-                });
-                
-                ui.add_space(8.0);
-                
-                // Navigation bar
-                ui.horizontal(|ui| {
-                    // Breadcrumb
-                    ui.label(egui::RichText::new("📍")
-                            .color(self.theme.accent));
-                    ui.label(egui::RichText::new(self.get_breadcrumb())
-                            .size(14.0)
+                    ui.label(egui::RichText::new("Values of environment variables whose name contains one of these are left out of the report:")
+                            .size(12.0)
                             .color(self.theme.on_surface_variant));
-                    
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        // Back button
-                        if !self.at_root() {
-                            if ui.button("⬅ Back").clicked() {
-                                self.go_back();
+                    ui.horizontal_wrapped(|ui| {
+                        let mut removed = None;
+                        for (i, pattern) in self.config.redact_patterns.iter().enumerate() {
+                            if ui.button(format!("{} ✕", pattern)).on_hover_text("Remove").clicked() {
+                                removed = Some(i);
                             }
                         }
-                        
-                        ui.add_space(16.0);
-                        
-                        // Search
-                        ui.label("🔍");
-                        let search_response = ui.add_sized([200.0, 24.0], 
-                            egui::TextEdit::singleline(&mut self.search_text)
-                                .hint_text("Search utilities..."));
-                        if search_response.changed() {
-                            self.apply_search_filter();
+                        if let Some(i) = removed {
+                            self.config.redact_patterns.remove(i);
+                            changed = true;
+                        }
+                        ui.add(egui::TextEdit::singleline(&mut self.redact_pattern_draft)
+                            .hint_text("e.g. api_key")
+                            .desired_width(120.0));
+                        let pattern = self.redact_pattern_draft.trim();
+                        if ui.add_enabled(!pattern.is_empty(), egui::Button::new("➕ Add")).clicked() {
+                            self.config.redact_patterns.push(pattern.to_string());
+                            self.redact_pattern_draft.clear();
+                            changed = true;
                         }
                     });
                 });
-                ui.add_space(8.0);
-            });
 
-        // Status bar
-        egui::TopBottomPanel::bottom("status_panel")
-            .min_height(32.0)
-            .show(ctx, |ui| {
-                ui.add_space(4.0);
-                ui.horizontal(|ui| {
-                    ui.label(egui::RichText::new(&self.status_message)
+            if changed {
+                if let Err(e) = self.config.save() {
+                    self.error_message = format!("Failed to save settings: {}", e);
+                }
+            }
+            if validation_changed {
+                self.apply_validation_mode();
+            }
+            if theme_changed {
+                self.theme = ModernTheme::from_config(&self.config);
+                ctx.set_visuals(create_modern_visuals(&self.theme));
+                // Cached layouts carry the colors of the previous theme
+                self.command_output_job = None;
+                for section in self.command_output_batch.iter_mut().flat_map(|batch| &mut batch.sections) {
+                    section.job = None;
+                }
+                for (_, section) in &mut self.output_dock {
+                    section.job = None;
+                }
+                if let Some(preview) = &mut self.preview {
+                    preview.highlighted = None;
+                }
+            }
+            if rescan {
+                self.rescan_system();
+            }
+            if copy_report {
+                self.copy_diagnostic_report(ctx);
+            }
+        }
+
+        // Selection window, listing everything picked for "Execute All" across tabs and directories
+        if self.show_selection && !self.selected_commands.is_empty() {
+            let mut open = true;
+            let mut remove = None;
+            let mut swap = None;
+            let mut clear_all = false;
+            let mut execute_all = false;
+            egui::Window::new("☑ Selection")
+                .open(&mut open)
+                .default_width(420.0)
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new(if self.config.max_parallel > 1 {
+                        "Commands start from the top, several at a time"
+                    } else {
+                        "Commands run from top to bottom, one at a time"
+                    })
                             .size(12.0)
                             .color(self.theme.on_surface_variant));
-                    
-                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        ui.label(egui::RichText::new(format!("{} items", self.filtered_items.len()))
-                                .size(12.0)
-                                .color(self.theme.on_surface_variant));
+                    ui.add_space(4.0);
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        let count = self.selected_commands.len();
+                        for (i, entry) in self.selected_commands.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                if ui.small_button("✕").on_hover_text("Remove from selection").clicked() {
+                                    remove = Some(i);
+                                }
+                                if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).on_hover_text("Run earlier").clicked() {
+                                    swap = Some((i - 1, i));
+                                }
+                                if ui.add_enabled(i + 1 < count, egui::Button::new("⬇").small()).on_hover_text("Run later").clicked() {
+                                    swap = Some((i, i + 1));
+                                }
+                                ui.label(egui::RichText::new(format!("{}. {}", i + 1, entry.path))
+                                        .color(self.theme.on_surface));
+                            });
+                        }
+                    });
+
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(!self.safe_mode(), egui::Button::new("▶ Execute All"))
+                            .on_hover_text(self.keymap.hint(ctx, Action::ExecuteSelection))
+                            .on_disabled_hover_text(tr(SAFE_MODE_HINT))
+                            .clicked() {
+                            execute_all = true;
+                        }
+                        if ui.button("🗑 Clear all").clicked() {
+                            clear_all = true;
+                        }
                     });
                 });
-                ui.add_space(4.0);
-            });
 
-        // Modern sidebar
-        if self.show_sidebar {
-            egui::SidePanel::left("sidebar")
-                .min_width(280.0)
-                .max_width(350.0)
+            self.show_selection = open;
+            if let Some(i) = remove {
+                let entry = self.selected_commands.remove(i);
+                self.status_message = tr_fmt("Removed {0} from selection", &[&entry.node.name]);
+            }
+            if let Some((a, b)) = swap {
+                self.selected_commands.swap(a, b);
+            }
+            if clear_all {
+                self.selected_commands.clear();
+                self.status_message = tr("Selection cleared");
+            }
+            if execute_all {
+                self.execute_all_selected();
+            }
+        }
+
+        // Queue panel, following each queued job from pending to finished
+        if self.show_queue && !self.queue.is_empty() {
+            let mut open = true;
+            let mut remove = None;
+            let mut swap = None;
+            let mut clear_finished = false;
+            let mut stop = false;
+            egui::Window::new("📋 Queue")
+                .open(&mut open)
+                .default_width(460.0)
                 .show(ctx, |ui| {
-                    ui.add_space(12.0);
-                    ui.label(egui::RichText::new("Categories")
-                            .size(18.0)
-                            .strong()
-                            .color(self.theme.on_surface));
-                    ui.add_space(8.0);
+                    egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                        for (i, item) in self.queue.iter().enumerate() {
+                            let pending = item.status == QueueStatus::Pending;
+                            let color = match item.status {
+                                QueueStatus::Pending | QueueStatus::Skipped => self.theme.on_surface_variant,
+                                QueueStatus::Running => self.theme.warning,
+                                QueueStatus::Succeeded => self.theme.success,
+                                QueueStatus::Failed => self.theme.danger,
+                            };
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(item.status.label())
+                                        .monospace()
+                                        .color(color));
+                                ui.label(egui::RichText::new(&item.path)
+                                        .color(self.theme.on_surface));
+                                if pending {
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        if ui.small_button("✕").on_hover_text("Remove from the queue").clicked() {
+                                            remove = Some(i);
+                                        }
+                                        let next_pending = self.queue.get(i + 1).is_some_and(|next| next.status == QueueStatus::Pending);
+                                        if ui.add_enabled(next_pending, egui::Button::new("⬇").small()).clicked() {
+                                            swap = Some((i, i + 1));
+                                        }
+                                        let previous_pending = i > 0 && self.queue[i - 1].status == QueueStatus::Pending;
+                                        if ui.add_enabled(previous_pending, egui::Button::new("⬆").small()).clicked() {
+                                            swap = Some((i - 1, i));
+                                        }
+                                    });
+                                }
+                            });
+                        }
+                    });
 
-                    let mut tab_to_switch = None;
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        for (i, tab) in self.tabs.iter().enumerate() {
-                            let selected = i == self.current_tab_index;
-                            
-                            let response = ui.selectable_label(selected, 
-                                egui::RichText::new(&tab.name)
-                                    .size(14.0)
-                                    .color(if selected { egui::Color32::WHITE } else { self.theme.on_surface }));
-                            
-                            if response.clicked() {
-                                tab_to_switch = Some(i);
-                            }
-                            
-                            ui.add_space(4.0);
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(self.queue.iter().any(|item| item.status.is_finished()), egui::Button::new("🗑 Clear finished")).clicked() {
+                            clear_finished = true;
+                        }
+                        if ui.add_enabled(self.executing_command && !STOPPING.load(Ordering::Relaxed), egui::Button::new("⏹ Stop batch"))
+                            .on_hover_text("Kill the running commands and skip everything still queued")
+                            .clicked() {
+                            stop = true;
                         }
                     });
-                    
-                    if let Some(tab_index) = tab_to_switch {
-                        self.switch_tab(tab_index);
-                    }
                 });
+
+            self.show_queue = open;
+            if stop {
+                self.stop_run();
+            }
+            if let Some(i) = remove {
+                self.remove_from_queue(i);
+            }
+            if let Some((a, b)) = swap {
+                if !queue::swap_pending(&mut self.queue, a, b) {
+                    self.status_message = tr("Only pending commands of the same batch can be reordered");
+                }
+            }
+            if clear_finished {
+                self.queue.retain(|item| !item.status.is_finished());
+            }
         }
 
-        // Main content with modern grid layout
-        egui::CentralPanel::default().show(ctx, |ui| {
-            if self.tabs.is_empty() {
-                ui.centered_and_justified(|ui| {
-                    ui.vertical_centered(|ui| {
-                        ui.spinner();
-                        ui.add_space(16.0);
-                        ui.label(egui::RichText::new("Loading system utilities...")
-                                .size(16.0)
-                                .color(self.theme.on_surface_variant));
+        // Schedule dialog
+        if let Some(draft) = &mut self.schedule_draft {
+            let mut schedule = false;
+            let mut cancel = false;
+            egui::Window::new("⏰ Schedule Command")
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label(egui::RichText::new(&draft.job.path)
+                            .strong()
+                            .color(self.theme.on_surface));
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut draft.at_time, false, "In");
+                        ui.add_enabled(!draft.at_time, egui::DragValue::new(&mut draft.delay_minutes).range(1..=7 * 24 * 60).suffix(" min"));
+                    });
+                    ui.horizontal(|ui| {
+                        ui.radio_value(&mut draft.at_time, true, "At");
+                        ui.add_enabled_ui(draft.at_time, |ui| {
+                            ui.add(egui::DragValue::new(&mut draft.hour).range(0..=23).custom_formatter(|n, _| format!("{:02}", n)));
+                            ui.label(":");
+                            ui.add(egui::DragValue::new(&mut draft.minute).range(0..=59).custom_formatter(|n, _| format!("{:02}", n)));
+                        });
+                    });
+                    if draft.job.node.danger {
+                        ui.add_space(8.0);
+                        ui.label(egui::RichText::new("⚠ This command makes changes that can't be undone, e.g. to disks or user accounts.")
+                                .color(self.theme.danger));
+                        ui.horizontal(|ui| {
+                            ui.label(format!("Type \"{}\" to confirm", draft.job.node.name));
+                            ui.add(egui::TextEdit::singleline(&mut draft.typed_name).desired_width(200.0));
+                        });
+                    }
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new("Schedules are kept in the app only: closing it cancels them.")
+                            .size(12.0)
+                            .color(self.theme.warning));
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        let confirmed = !draft.job.node.danger || draft.typed_name.trim() == draft.job.node.name;
+                        if ui.add_enabled(confirmed, egui::Button::new("⏰ Schedule")).clicked() {
+                            schedule = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
+                        }
                     });
                 });
-                return;
+
+            if schedule {
+                let draft = self.schedule_draft.take().unwrap();
+                let now = SystemTime::now();
+                let due = if draft.at_time {
+                    next_clock_time(now, draft.hour, draft.minute)
+                } else {
+                    now + Duration::from_secs(u64::from(draft.delay_minutes) * 60)
+                };
+                self.status_message = tr_fmt("Scheduled {0} for {1}", &[&draft.job.node.name, &format_clock(due)]);
+                self.scheduled.push(ScheduledJob { job: draft.job, due });
+                self.scheduled.sort_by_key(|scheduled| scheduled.due);
+            } else if cancel {
+                self.schedule_draft = None;
             }
+        }
 
-            let mut action = None;
-            let mut action_index = 0;
-            
-            egui::ScrollArea::vertical()
-                .auto_shrink([false; 2])
-                .show(ui, |ui| {
-                    ui.add_space(16.0);
-                    
-                    // Up directory card
-                    if !self.at_root() {
-                        egui::Frame::none()
-                            .fill(self.theme.surface_variant)
-                            .rounding(12.0)
-                            .inner_margin(16.0)
-                            .stroke(egui::Stroke::new(1.0, self.theme.border))
-                            .show(ui, |ui| {
-                                ui.horizontal(|ui| {
-                                    if ui.button("📁 .. Go Back").clicked() {
-                                        action = Some("go_back".to_string());
-                                    }
-                                });
-                            });
-                        ui.add_space(16.0);
+        // Scheduled commands panel
+        if self.show_scheduled && !self.scheduled.is_empty() {
+            let mut open = true;
+            let mut cancel = None;
+            egui::Window::new("⏰ Scheduled")
+                .open(&mut open)
+                .default_width(420.0)
+                .show(ctx, |ui| {
+                    let now = SystemTime::now();
+                    for (i, scheduled) in self.scheduled.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.small_button("✕").on_hover_text("Cancel").clicked() {
+                                cancel = Some(i);
+                            }
+                            let remaining = scheduled.due.duration_since(now).unwrap_or_default();
+                            ui.label(egui::RichText::new(format!("{} (in {})", format_clock(scheduled.due), format_duration(remaining)))
+                                    .monospace()
+                                    .color(self.theme.on_surface_variant));
+                            ui.label(egui::RichText::new(&scheduled.job.path)
+                                    .color(self.theme.on_surface));
+                        });
                     }
+                    ui.add_space(8.0);
+                    ui.label(egui::RichText::new("Closing the app cancels all scheduled commands.")
+                            .size(12.0)
+                            .color(self.theme.warning));
+                });
+            // Keep the countdowns moving
+            ctx.request_repaint_after(Duration::from_secs(1));
 
-                    // Modern grid layout
-                    let available_width = ui.available_width();
-                    let card_width = 350.0;
-                    let spacing = 16.0;
-                    let cols = ((available_width + spacing) / (card_width + spacing)).floor() as usize;
-                    let cols = cols.max(1);
-                    
-                    ui.columns(cols, |columns| {
-                        for (i, entry) in self.filtered_items.iter().enumerate() {
-                            let col = i % cols;
-                            if let Some(entry_action) = self.render_category_card(&mut columns[col], entry, i) {
-                                action = Some(entry_action);
-                                action_index = i;
-                            }
-                            columns[col].add_space(16.0);
+            self.show_scheduled = open;
+            if let Some(i) = cancel {
+                let scheduled = self.scheduled.remove(i);
+                self.status_message = tr_fmt("Cancelled the scheduled run of {0}", &[&scheduled.job.node.name]);
+            }
+        }
+
+        // Self-test report
+        if let Some(checks) = &self.self_test {
+            let mut open = true;
+            egui::Window::new("🩺 Self-test")
+                .open(&mut open)
+                .default_width(520.0)
+                .show(ctx, |ui| {
+                    let (summary, color) = if selftest::failed(checks) {
+                        ("Commands can't run until the failed checks are fixed.", self.theme.danger)
+                    } else if checks.iter().all(|check| check.passed) {
+                        ("Everything commands need was found.", self.theme.success)
+                    } else {
+                        ("Commands can run, but some features won't work.", self.theme.warning)
+                    };
+                    ui.label(egui::RichText::new(summary)
+                            .strong()
+                            .color(color));
+                    ui.add_space(8.0);
+                    egui::Grid::new("self_test").num_columns(2).spacing([12.0, 6.0]).show(ui, |ui| {
+                        for check in checks {
+                            let color = match (check.passed, check.critical) {
+                                (true, _) => self.theme.success,
+                                (false, true) => self.theme.danger,
+                                (false, false) => self.theme.warning,
+                            };
+                            ui.label(egui::RichText::new(format!("{} {}", check.icon(), check.name))
+                                    .color(color));
+                            ui.label(egui::RichText::new(&check.detail)
+                                    .color(self.theme.on_surface_variant));
+                            ui.end_row();
                         }
                     });
+                });
+            if !open {
+                self.self_test = None;
+            }
+        }
 
-                    if self.filtered_items.is_empty() {
-                        ui.centered_and_justified(|ui| {
-                            ui.vertical_centered(|ui| {
-                                ui.label(egui::RichText::new("🔍")
-                                        .size(48.0)
-                                        .color(self.theme.on_surface_variant));
-                                ui.add_space(16.0);
-                                let message = if !self.search_text.is_empty() {
-                                    "No utilities match your search"
-                                } else {
-                                    "This category is empty"
-                                };
-                                ui.label(egui::RichText::new(message)
-                                        .size(16.0)
-                                        .color(self.theme.on_surface_variant));
-                            });
-                        });
+        // Statistics window
+        if self.show_stats {
+            let mut reset = false;
+            egui::Window::new("📊 Statistics")
+                .open(&mut self.show_stats)
+                .default_width(420.0)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let total = self.history.len();
+                    let successes = self.history.iter().filter(|entry| entry.success).count();
+                    let failures = total - successes;
+                    let ratio = |count: usize| if total == 0 { 0.0 } else { count as f32 / total as f32 };
+
+                    ui.label(egui::RichText::new(format!("Total runs: {}", total))
+                            .size(16.0)
+                            .strong()
+                            .color(self.theme.on_surface));
+                    ui.add_space(8.0);
+                    ui.add(egui::ProgressBar::new(ratio(successes))
+                           .fill(self.theme.success)
+                           .text(format!("✅ {} succeeded", successes)));
+                    ui.add(egui::ProgressBar::new(ratio(failures))
+                           .fill(self.theme.danger)
+                           .text(format!("❌ {} failed", failures)));
+
+                    ui.add_space(12.0);
+                    ui.label(egui::RichText::new("Most run")
+                            .strong()
+                            .color(self.theme.on_surface));
+
+                    let mut counts: Vec<(&str, usize)> = Vec::new();
+                    for entry in &self.history {
+                        match counts.iter_mut().find(|(path, _)| *path == entry.path) {
+                            Some((_, count)) => *count += 1,
+                            None => counts.push((&entry.path, 1)),
+                        }
                     }
-                });
+                    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
 
-            // Handle actions
-            if let Some(action_type) = action {
-                match action_type.as_str() {
-                    "go_back" => self.go_back(),
-                    "enter" => {
-                        self.selected_index = action_index;
-                        self.enter_directory();
+                    if counts.is_empty() {
+                        ui.label(egui::RichText::new("No commands have been run yet")
+                                .color(self.theme.on_surface_variant));
                     }
-                    "execute" => {
-                        self.selected_index = action_index;
-                        self.execute_selected_command();
+                    let max = counts.first().map_or(1, |(_, count)| *count);
+                    for (path, count) in counts.iter().take(5) {
+                        ui.add(egui::ProgressBar::new(*count as f32 / max as f32)
+                               .fill(self.theme.primary)
+                               .text(format!("{} ({})", path, count)));
                     }
-                    "preview" => {
-                        if let Some(entry) = self.filtered_items.get(action_index) {
-                            self.command_output = format!("📋 Command Preview\n\nName: {}\nDescription: {}\nTask List: {}", 
-                                                         entry.node.name, entry.node.description, entry.node.task_list);
-                            self.show_command_output = true;
+
+                    ui.add_space(12.0);
+                    ui.horizontal(|ui| {
+                        if self.confirm_reset_stats {
+                            ui.label(egui::RichText::new("Clear the whole execution history?")
+                                    .color(self.theme.warning));
+                            if ui.button("Yes, reset").clicked() {
+                                reset = true;
+                                self.confirm_reset_stats = false;
+                            }
+                            if ui.button("Cancel").clicked() {
+                                self.confirm_reset_stats = false;
+                            }
+                        } else if ui.button("🗑 Reset statistics").clicked() {
+                            self.confirm_reset_stats = true;
                         }
+                    });
+                });
+
+            if reset {
+                match history::clear() {
+                    Ok(()) => {
+                        self.set_history(Vec::new());
+                        self.status_message = tr("Statistics reset");
                     }
-                    "multi_select" => {
-                        self.selected_index = action_index;
-                        self.toggle_multi_select();
-                        self.multi_select = true;
-                    }
-                    _ => {}
+                    Err(e) => self.error_message = format!("Failed to clear history: {}", e),
                 }
             }
-        });
+        }
 
-        // Modern command output window
-        if self.show_command_output {
-            egui::Window::new("Command Output")
-                .default_width(700.0)
-                .default_height(500.0)
-                .resizable(true)
+        // Confirmation dialog, showing exactly what each command will run as
+        let safe_mode = self.safe_mode();
+        if let Some(pending) = &mut self.pending_run {
+            let mut run = false;
+            let mut cancel = false;
+            egui::Window::new("▶ Confirm Execution")
+                .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+                .collapsible(false)
+                .resizable(false)
+                .default_width(640.0)
                 .show(ctx, |ui| {
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        ui.add(egui::TextEdit::multiline(&mut self.command_output.as_str())
-                               .font(egui::TextStyle::Monospace)
-                               .desired_rows(20)
-                               .desired_width(f32::INFINITY));
+                    egui::ScrollArea::vertical().max_height(420.0).show(ui, |ui| {
+                        let jobs = pending.batch.jobs.iter().zip(&pending.resolved).zip(&pending.missing).zip(&mut pending.typed_names).zip(&mut pending.dont_ask).zip(&mut pending.dry_runs);
+                        for (((((Job { path, node, .. }, resolved), missing), typed_name), dont_ask), dry_runs) in jobs {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(path)
+                                        .strong()
+                                        .color(self.theme.on_surface));
+                                // Dangerous commands always ask, so they can't be exempted
+                                if !node.danger {
+                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                        ui.checkbox(dont_ask, "Don't ask again for this command");
+                                    });
+                                }
+                            });
+                            if node.danger {
+                                ui.label(egui::RichText::new("⚠ This command makes changes that can't be undone, e.g. to disks or user accounts.")
+                                        .color(self.theme.danger));
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("Type \"{}\" to confirm", node.name));
+                                    ui.add(egui::TextEdit::singleline(typed_name)
+                                        .id_salt(("confirm_danger", path))
+                                        .desired_width(200.0));
+                                });
+                            }
+                            if !missing.is_empty() {
+                                ui.label(egui::RichText::new(format!("⚠ Not installed: {}", missing.join(", ")))
+                                        .color(self.theme.danger));
+                            }
+                            let Some(resolved) = resolved else {
+                                ui.label(egui::RichText::new("Nothing to run")
+                                        .color(self.theme.on_surface_variant));
+                                continue;
+                            };
+
+                            egui::Frame::none()
+                                .fill(self.theme.background)
+                                .stroke(egui::Stroke::new(1.0, self.theme.border))
+                                .rounding(6.0)
+                                .inner_margin(8.0)
+                                .show(ui, |ui| {
+                                    ui.set_width(ui.available_width());
+                                    ui.horizontal_top(|ui| {
+                                        if ui.button("📋 Copy Command").clicked() {
+                                            ui.output_mut(|o| o.copied_text = resolved.line.clone());
+                                            self.status_message = tr("Command copied to clipboard");
+                                            self.toasts.push(Severity::Info, self.status_message.clone());
+                                        }
+                                        ui.add(egui::Label::new(egui::RichText::new(&resolved.line)
+                                                .monospace()
+                                                .color(self.theme.on_surface))
+                                            .wrap());
+                                    });
+                                });
+
+                            egui::Grid::new(path).num_columns(2).show(ui, |ui| {
+                                for (label, value) in &resolved.details {
+                                    ui.label(egui::RichText::new(*label)
+                                            .color(self.theme.on_surface_variant));
+                                    ui.label(egui::RichText::new(value)
+                                            .monospace()
+                                            .color(self.theme.on_surface));
+                                    ui.end_row();
+                                }
+                            });
+
+                            for (i, dry_run) in dry_runs.iter_mut().enumerate() {
+                                if !dry_run.poll() {
+                                    ui.ctx().request_repaint_after(Duration::from_millis(100));
+                                }
+                                egui::CollapsingHeader::new(egui::RichText::new(format!("📦 What would change: {}", dry_run.line))
+                                        .color(self.theme.on_surface_variant))
+                                    .id_salt(("dry_run", path, i))
+                                    .default_open(true)
+                                    .show(ui, |ui| match &dry_run.output {
+                                        None => {
+                                            ui.horizontal(|ui| {
+                                                ui.spinner();
+                                                ui.label(egui::RichText::new("Asking the package manager...")
+                                                        .color(self.theme.on_surface_variant));
+                                            });
+                                        }
+                                        Some(output) => {
+                                            egui::ScrollArea::vertical()
+                                                .id_salt(("dry_run_output", path, i))
+                                                .max_height(160.0)
+                                                .show(ui, |ui| {
+                                                    ui.label(egui::RichText::new(output.trim_end())
+                                                            .monospace()
+                                                            .size(12.0)
+                                                            .color(self.theme.on_surface));
+                                                });
+                                        }
+                                    });
+                            }
+                            ui.add_space(12.0);
+                        }
                     });
-                    
-                    ui.add_space(12.0);
+
+                    if let Some(free) = pending.free_space {
+                        let minimum = self.config.min_free_space_mb * 1024 * 1024;
+                        let (text, color) = if free < minimum {
+                            (format!("💾 Only {} free on /, less than the {} minimum. The install may fill the disk and stop halfway.",
+                                     format_size(free), format_size(minimum)), self.theme.warning)
+                        } else {
+                            (format!("💾 {} free on /", format_size(free)), self.theme.on_surface_variant)
+                        };
+                        ui.label(egui::RichText::new(text)
+                                .color(color));
+                        ui.add_space(8.0);
+                    }
+
+                    let any_missing = pending.missing.iter().any(|missing| !missing.is_empty());
+                    if any_missing {
+                        ui.label(egui::RichText::new("Some commands need programs that are not installed and will likely fail.")
+                                .color(self.theme.warning));
+                        ui.add_space(8.0);
+                    }
+                    let confirmed = pending.confirmed();
                     ui.horizontal(|ui| {
-                        if ui.button("📋 Copy").clicked() {
-                            ui.output_mut(|o| o.copied_text = self.command_output.clone());
-                            self.status_message = "Output copied to clipboard".to_string();
+                        let run_button = egui::Button::new(if any_missing { "▶ Run anyway" } else { "▶ Run" });
+                        let hint = if safe_mode { SAFE_MODE_HINT } else { "Type the name of each dangerous command first" };
+                        if ui.add_enabled(confirmed && !safe_mode, run_button)
+                            .on_disabled_hover_text(tr(hint))
+                            .clicked() {
+                            run = true;
+                        }
+                        if ui.button("Cancel").clicked() {
+                            cancel = true;
                         }
-                        
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("✕ Close").clicked() {
-                                self.show_command_output = false;
-                            }
-                        });
                     });
                 });
+
+            if run {
+                self.confirm_pending_run();
+            } else if cancel {
+                self.pending_run = None;
+                self.status_message = tr("Execution cancelled");
+            }
         }
 
+        self.render_close_confirmation(ctx);
+
         // Error dialog
         if !self.error_message.is_empty() {
             egui::Window::new("⚠️ Error")
@@ -788,96 +4635,805 @@ impl eframe::App for LinutilApp {
     }
 }
 
-fn execute_command_node(node: &ListNode) -> CommandResult {
-    match &node.command {
-        LinutilCommand::Raw(cmd) => {
-            execute_raw_command(cmd)
-        },
-        LinutilCommand::LocalFile { executable, args, file } => {
-            execute_script_file(executable, args, file)
-        },
-        LinutilCommand::None => {
-            CommandResult {
-                success: false,
-                output: "Cannot execute directory".to_string(),
-                error: Some("This is a directory, not an executable command".to_string()),
+// Editable list of environment variables, with a row for adding a new one. Returns whether the
+// variables changed.
+fn edit_env(ui: &mut egui::Ui, vars: &mut BTreeMap<String, String>, draft: &mut (String, String)) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    egui::Grid::new(ui.id().with("env")).num_columns(3).show(ui, |ui| {
+        for (key, value) in vars.iter_mut() {
+            ui.label(egui::RichText::new(key).monospace());
+            changed |= ui.add(egui::TextEdit::singleline(value).desired_width(200.0)).changed();
+            if ui.small_button("✕").on_hover_text("Remove variable").clicked() {
+                remove = Some(key.clone());
             }
+            ui.end_row();
+        }
+
+        ui.add(egui::TextEdit::singleline(&mut draft.0).hint_text("NAME").desired_width(120.0));
+        ui.add(egui::TextEdit::singleline(&mut draft.1).hint_text("value").desired_width(200.0));
+        let valid = !draft.0.is_empty() && !draft.0.contains(|c: char| c == '=' || c == '\0' || c.is_whitespace());
+        if ui.add_enabled(valid, egui::Button::new("➕")).on_hover_text("Add variable").clicked() {
+            let (key, value) = std::mem::take(draft);
+            vars.insert(key, value);
+            changed = true;
         }
+        ui.end_row();
+    });
+
+    if let Some(key) = remove {
+        vars.remove(&key);
+        changed = true;
     }
+    changed
 }
 
-fn execute_raw_command(cmd: &str) -> CommandResult {
-    let output = Command::new("sh")
-        .arg("-c")
-        .arg(cmd)
-        .env("DEBIAN_FRONTEND", "noninteractive")
-        .output();
-        
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            
-            let success = output.status.success();
-            let result_output = if stdout.is_empty() && !stderr.is_empty() {
-                stderr.clone()
-            } else if !stdout.is_empty() {
-                stdout
-            } else {
-                "Command executed successfully".to_string()
-            };
-            
-            CommandResult {
-                success,
-                output: result_output,
-                error: if success { None } else { Some(stderr) },
+// Breadcrumb of a node, e.g. "System Setup › Arch Linux › Paru AUR Helper"
+fn node_path(tab_name: &str, node: linutil_core::ego_tree::NodeRef<Rc<ListNode>>) -> String {
+    let mut names: Vec<&str> = node
+        .ancestors()
+        .filter(|ancestor| ancestor.parent().is_some())
+        .map(|ancestor| ancestor.value().name.as_str())
+        .collect();
+    names.reverse();
+    names.insert(0, tab_name);
+    names.push(&node.value().name);
+    names.join(" › ")
+}
+
+// Everything the tab data defines about a command, plus its path and body
+fn command_json(path: &str, node: &ListNode, body: &str) -> String {
+    let command = match &node.command {
+        LinutilCommand::Raw(cmd) => serde_json::json!({ "type": "command", "command": cmd }),
+        LinutilCommand::LocalFile { executable, args, file } => serde_json::json!({
+            "type": "script",
+            "executable": executable,
+            "args": args,
+            "file": file,
+        }),
+        LinutilCommand::None => serde_json::json!({ "type": "directory" }),
+    };
+    let requirements: Vec<_> = node.requirements.iter()
+        .map(|requirement| serde_json::json!({ "description": requirement.description, "met": requirement.met }))
+        .collect();
+    let json = serde_json::json!({
+        "name": node.name,
+        "description": node.description,
+        "path": path,
+        "command": command,
+        "body": body,
+        "task_list": node.task_list,
+        "multi_select": node.multi_select,
+        "dependencies": node.dependencies,
+        "modifies": node.modifies,
+        "verify": node.verify,
+        "danger": node.danger,
+        "requirements": requirements,
+    });
+    serde_json::to_string_pretty(&json).unwrap_or_default()
+}
+
+// Builds a command line that can be pasted into a terminal and works from any directory
+fn command_line(command: &LinutilCommand) -> Option<String> {
+    match command {
+        LinutilCommand::Raw(cmd) => Some(cmd.clone()),
+        LinutilCommand::LocalFile { executable, args, file } => {
+            let (_, args) = absolute_script_args(args, file);
+            let mut line = executable.clone();
+            for arg in args {
+                line.push(' ');
+                line.push_str(&shell_quote(&arg));
             }
-        },
-        Err(e) => {
-            CommandResult {
-                success: false,
-                output: format!("Failed to execute command: {}", e),
-                error: Some(e.to_string()),
+            Some(line)
+        }
+        LinutilCommand::None => None,
+    }
+}
+
+fn shell_quote(arg: &str) -> std::borrow::Cow<'_, str> {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "_-./=:,+@%".contains(c);
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.into()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''")).into()
+    }
+}
+
+// Name of the program running a script: the program after /usr/bin/env, else the executable's file
+// name, e.g. "python3" for both "#!/usr/bin/env python3" and "#!/usr/bin/python3"
+fn interpreter_name(executable: &str, args: &[String]) -> String {
+    let name = |path: &str| Path::new(path).file_name().map_or(path.to_string(), |name| name.to_string_lossy().to_string());
+    if name(executable) == "env" {
+        // Skip options like the -S of "#!/usr/bin/env -S python3 -u"
+        if let Some(program) = args.iter().find(|arg| !arg.starts_with('-') && !arg.contains('=')) {
+            return name(program);
+        }
+    }
+    name(executable)
+}
+
+// Spells out the process a command runs as. It is read back from the same Command the executors
+// spawn, so the confirmation dialog can't show something different from what actually runs.
+fn resolve_command(command: &LinutilCommand, opts: &ExecOptions) -> Option<ResolvedCommand> {
+    let process = build_process(command, opts)?;
+
+    let program = process.get_program().to_string_lossy();
+    let args: Vec<String> = process.get_args()
+        .map(|arg| shell_quote(&arg.to_string_lossy()).into_owned())
+        .collect();
+    let env = process.get_envs()
+        .filter_map(|(key, value)| Some(format!("{}={}", key.to_string_lossy(), shell_quote(&value?.to_string_lossy()))))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let working_dir = working_dir(&process);
+
+    let mut line = format!("{} {}", shell_quote(&program), args.join(" "));
+    if !env.is_empty() {
+        line = format!("{} {}", env, line);
+    }
+
+    let mut details = Vec::new();
+    if let LinutilCommand::LocalFile { executable, file, .. } = command {
+        details.push(("Executable", executable.clone()));
+        details.push(("Arguments", args.join(" ")));
+        let script = std::fs::canonicalize(file).unwrap_or_else(|_| file.clone());
+        details.push(("Script", script.display().to_string()));
+    }
+    details.push(("Working directory", working_dir.display().to_string()));
+    if !env.is_empty() {
+        details.push(("Environment", env));
+    }
+
+    Some(ResolvedCommand { line, details })
+}
+
+// Name of the tab of the user's own scripts
+const SCRIPTS_TAB: &str = "My Scripts";
+
+// The tabs of the tab data with the "My Scripts" tab after them, and the files of the scripts
+// directory that were skipped. Nobody has to have that directory, so it only counts as a problem
+// when it was configured
+pub fn load_all_tabs(config: &AppConfig) -> Result<(TabList, Vec<String>), String> {
+    let mut tabs = try_get_tabs(config.validate)?;
+    let Some(dir) = config.scripts_dir() else {
+        return Ok((tabs, Vec::new()));
+    };
+    if !dir.exists() {
+        let problems = if config.scripts_dir.trim().is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("{} does not exist", dir.display())]
+        };
+        return Ok((tabs, problems));
+    }
+    match scripts_tab(SCRIPTS_TAB, &dir, config.validate) {
+        Ok((tab, skipped)) => {
+            tabs.extend(tab);
+            Ok((tabs, skipped))
+        }
+        Err(e) => Ok((tabs, vec![e])),
+    }
+}
+
+// Runs the jobs of a batch, with at most max_parallel of them at a time, reporting each result as
+// soon as it is available
+fn run_batch(batch: QueuedBatch, event_tx: &mpsc::Sender<CommandEvent>) {
+    let failed = AtomicBool::new(false);
+    thread::scope(|scope| {
+        for _ in 0..batch.max_parallel.max(1) {
+            scope.spawn(|| loop {
+                if stop_requested() || (batch.stop_on_failure && failed.load(Ordering::Relaxed)) {
+                    break;
+                }
+                // Take the next job without holding the lock while it runs
+                let job = batch.queue.lock().unwrap().pop_front();
+                let Some(job) = job else {
+                    break;
+                };
+                let opts = job.exec_options(&batch.exec);
+                let Job { id, path, node, .. } = job;
+                let _ = event_tx.send(CommandEvent::Started { id });
+                let before = diff::snapshot(&node.modifies);
+                let mut result = execute_with_retries(&node, &opts, batch.retry, |attempt, delay| {
+                    let _ = event_tx.send(CommandEvent::Retrying { id, attempt, delay });
+                });
+                result.file_changes = diff::changes(before);
+                verify_result(&node, &opts, &mut result);
+                if !result.success {
+                    failed.store(true, Ordering::Relaxed);
+                }
+                if let Some(log) = &batch.exec_log {
+                    let entry = exec_log::LogEntry::new(&path, command_line(&node.command), &result);
+                    if let Err(e) = exec_log::append(log, &entry) {
+                        eprintln!("Failed to write the command log {}: {}", log.display(), e);
+                    }
+                }
+                let _ = event_tx.send(CommandEvent::Finished { id, path, node, result: Box::new(result) });
+            });
+        }
+    });
+
+    // Only a stopped batch has jobs left at this point. Batches queued behind a stopped run end up
+    // here right away, as the flag stays set until everything has been reported
+    for Job { id, node, .. } in batch.queue.lock().unwrap().drain(..) {
+        let _ = event_tx.send(CommandEvent::Skipped { id, node });
+    }
+}
+
+// Runs a command, and runs it again while it fails if the retry policy covers it. Waits twice as
+// long before each further attempt. on_retry gets the number of the next attempt and the delay.
+fn execute_with_retries(node: &ListNode, opts: &ExecOptions, retry: RetryPolicy,
+                        mut on_retry: impl FnMut(u32, Duration)) -> CommandResult {
+    let mut result = run_node(node, opts);
+    if result.success || retry.attempts == 0 || !queue::is_retryable(node) {
+        return result;
+    }
+
+    let mut failed_attempts = Vec::new();
+    let mut delay = retry.base_delay;
+    while !result.success && failed_attempts.len() < retry.attempts as usize {
+        on_retry(failed_attempts.len() as u32 + 2, delay);
+        // Sleep in steps, so closing the app or stopping the run doesn't wait for the backoff
+        let wake_at = Instant::now() + delay;
+        while Instant::now() < wake_at {
+            if stop_requested() {
+                break;
             }
+            thread::sleep(wake_at.saturating_duration_since(Instant::now()).min(Duration::from_millis(100)));
         }
+        if stop_requested() {
+            break;
+        }
+        failed_attempts.push(std::mem::replace(&mut result, run_node(node, opts)));
+        delay *= 2;
     }
+    result.failed_attempts = failed_attempts;
+    result
 }
 
-fn execute_script_file(executable: &str, args: &[String], file: &std::path::PathBuf) -> CommandResult {
-    let script_dir = file.parent().unwrap_or_else(|| std::path::Path::new("."));
-    
-    let output = Command::new(executable)
-        .args(args)
-        .current_dir(script_dir)
-        .env("DEBIAN_FRONTEND", "noninteractive")
-        .output();
-        
-    match output {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-            
-            let success = output.status.success();
-            let result_output = if stdout.is_empty() && !stderr.is_empty() {
-                stderr.clone()
-            } else if !stdout.is_empty() {
-                stdout
+// Runs the verify command of a node after it succeeded, as an inline command with the node's
+// environment. Exiting with 0 is not enough if what the command was meant to set up isn't there,
+// so a failed verification fails the result
+pub fn verify_result(node: &ListNode, opts: &ExecOptions, result: &mut CommandResult) {
+    let Some(verify) = node.verify.as_ref().filter(|_| result.success && !opts.dry_run && !stop_requested()) else {
+        return;
+    };
+    let check = ListNode { command: LinutilCommand::Raw(verify.clone()), ..node.clone() };
+    let checked = run_node(&check, opts);
+    let output = if checked.lines.is_empty() {
+        checked.output
+    } else {
+        checked.lines.iter().map(|line| line.text.as_str()).collect::<Vec<_>>().join("\n")
+    };
+    if !checked.success {
+        result.success = false;
+        result.error = Some(format!("Verification failed: {}", verify));
+    }
+    result.verification = Some(Verification { command: verify.clone(), passed: checked.success, output });
+}
+
+// "512 MB", or "3.2 GB" from 1 GB on
+fn format_size(bytes: u64) -> String {
+    const MB: u64 = 1024 * 1024;
+    if bytes < 1024 * MB {
+        format!("{} MB", bytes / MB)
+    } else {
+        format!("{:.1} GB", bytes as f64 / (1024 * MB) as f64)
+    }
+}
+
+// "12.4s", or "2m 05s" for longer runs
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+// Wall clock time of day, in the local time zone
+fn format_clock(time: SystemTime) -> String {
+    let tm = local_time(time);
+    format!("{:02}:{:02}:{:02}", tm.tm_hour, tm.tm_min, tm.tm_sec)
+}
+
+fn local_time(time: SystemTime) -> libc::tm {
+    let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as libc::time_t;
+    // All zeros is a valid tm, and localtime_r fills in every field
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&secs, &mut tm) };
+    tm
+}
+
+// The next time the local clock shows hour:minute, today or tomorrow
+fn next_clock_time(now: SystemTime, hour: u32, minute: u32) -> SystemTime {
+    let now_secs = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()) as libc::time_t;
+    let mut tm = local_time(now);
+    let mut due = now_secs;
+    for day in 0..2 {
+        tm.tm_mday += day;
+        tm.tm_hour = hour as i32;
+        tm.tm_min = minute as i32;
+        tm.tm_sec = 0;
+        // mktime works out whether daylight saving time is in effect on that day
+        tm.tm_isdst = -1;
+        due = unsafe { libc::mktime(&mut tm) };
+        if due > now_secs {
+            break;
+        }
+    }
+    UNIX_EPOCH + Duration::from_secs(due.max(0) as u64)
+}
+
+// "just now", "5m ago", "2h ago", "3d ago"
+fn format_ago(unix_secs: u64) -> String {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    match now.saturating_sub(unix_secs) {
+        secs if secs < 60 => "just now".to_string(),
+        secs if secs < 3600 => format!("{}m ago", secs / 60),
+        secs if secs < 86400 => format!("{}h ago", secs / 3600),
+        secs => format!("{}d ago", secs / 86400),
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let millis = elapsed.as_millis();
+    format!("[{:02}:{:02}.{:03}]", millis / 60_000, millis / 1000 % 60, millis % 1000)
+}
+
+// The output of a batch, one collapsible section per command
+struct BatchOutput {
+    // Makes the ids of the sections unique per batch, so each batch starts with the default
+    // expanded state
+    id: u64,
+    summary: String,
+    sections: Vec<OutputSection>,
+    skipped: Vec<String>,
+    skipped_heading: &'static str,
+}
+
+struct OutputSection {
+    title: String,
+    success: bool,
+    verified: Option<bool>,
+    output: OutputText,
+    // Layout of output, built on first show
+    job: Option<egui::text::LayoutJob>,
+}
+
+// Green "Verified" or red "Verification failed" for a command with a verify command
+fn verification_badge(ui: &mut egui::Ui, theme: &ModernTheme, passed: bool) {
+    let (text, color) = if passed {
+        ("✔ Verified", theme.success)
+    } else {
+        ("✘ Verification failed", theme.danger)
+    };
+    ui.label(egui::RichText::new(text)
+            .strong()
+            .background_color(color.gamma_multiply(0.3))
+            .color(color));
+}
+
+// Read-only, selectable view of captured output. Laying out colored text is expensive, so the
+// layout is cached in job until the caller resets it
+fn output_view(ui: &mut egui::Ui, output: &OutputText, job: &mut Option<egui::text::LayoutJob>, theme: &ModernTheme, rows: usize, collapse: bool) {
+    let job = job.get_or_insert_with(|| {
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        if collapse {
+            output.collapse_repeats().layout_job(theme, font_id)
+        } else {
+            output.layout_job(theme, font_id)
+        }
+    });
+    // The job holds the text actually shown, which differs from the output when collapsed
+    let text = job.text.clone();
+    let mut layouter = |ui: &egui::Ui, _text: &str, wrap_width: f32| {
+        let mut job = job.clone();
+        job.wrap.max_width = wrap_width;
+        ui.fonts(|fonts| fonts.layout_job(job))
+    };
+    ui.add(egui::TextEdit::multiline(&mut text.as_str())
+           .font(egui::TextStyle::Monospace)
+           .desired_rows(rows)
+           .desired_width(f32::INFINITY)
+           .layouter(&mut layouter));
+}
+
+// Text of the output window, remembering which parts came from stderr so they can be colored
+#[derive(Default)]
+pub struct OutputText {
+    pub text: String,
+    /// Byte ranges of stderr lines, and whether the command that printed them failed
+    pub stderr: Vec<(std::ops::Range<usize>, bool)>,
+}
+
+impl OutputText {
+    fn push(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    fn push_stderr(&mut self, text: &str, failed: bool) {
+        let start = self.text.len();
+        self.text.push_str(text);
+        self.stderr.push((start..self.text.len(), failed));
+    }
+
+    // stderr of a successful command is usually warnings, so it gets the warning color instead of
+    // the danger one
+    fn layout_job(&self, theme: &ModernTheme, font_id: egui::FontId) -> egui::text::LayoutJob {
+        let mut job = egui::text::LayoutJob::default();
+        let format = |color| egui::TextFormat::simple(font_id.clone(), color);
+        let mut pos = 0;
+        for (range, failed) in &self.stderr {
+            job.append(&self.text[pos..range.start], 0.0, format(theme.on_surface));
+            let color = if *failed { theme.danger } else { theme.warning };
+            job.append(&self.text[range.clone()], 0.0, format(color));
+            pos = range.end;
+        }
+        job.append(&self.text[pos..], 0.0, format(theme.on_surface));
+        job
+    }
+
+    // Copy for display with runs of identical lines collapsed. Lines only merge within the same
+    // stream, so the stderr coloring stays right
+    fn collapse_repeats(&self) -> OutputText {
+        let mut collapsed = OutputText::default();
+        let mut pos = 0;
+        for (range, failed) in &self.stderr {
+            collapsed.push(&collapse_lines(&self.text[pos..range.start]));
+            collapsed.push_stderr(&collapse_lines(&self.text[range.clone()]), *failed);
+            pos = range.end;
+        }
+        collapsed.push(&collapse_lines(&self.text[pos..]));
+        collapsed
+    }
+}
+
+// "line … (×N)" for N identical consecutive lines. Blank lines are kept as they are
+fn collapse_lines(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut lines = text.split_inclusive('\n').peekable();
+    while let Some(line) = lines.next() {
+        let mut count = 1;
+        while lines.peek() == Some(&line) {
+            lines.next();
+            count += 1;
+        }
+        let (content, newline) = line.strip_suffix('\n').map_or((line, ""), |content| (content, "\n"));
+        if count == 1 || content.trim().is_empty() {
+            collapsed.push_str(&line.repeat(count));
+        } else {
+            collapsed.push_str(&format!("{} … (×{}){}", content, count, newline));
+        }
+    }
+    collapsed
+}
+
+// Opens the directory of a file in the file manager. File managers implementing the freedesktop
+// FileManager1 interface get the file itself, so they can select it; others get its directory
+// through xdg-open.
+fn reveal_in_file_manager(file: &Path) -> std::io::Result<()> {
+    if !file.exists() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "The file does not exist"));
+    }
+    // URIs need everything but unreserved characters and the separators percent-encoded
+    let path: String = file.to_string_lossy()
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect();
+    let uri = format!("array:string:file://{}", path);
+    let selected = Command::new("dbus-send")
+        .args(["--session", "--print-reply", "--dest=org.freedesktop.FileManager1", "--type=method_call",
+               "/org/freedesktop/FileManager1", "org.freedesktop.FileManager1.ShowItems"])
+        .arg(uri)
+        .arg("string:")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if selected {
+        return Ok(());
+    }
+
+    let dir = file.parent().unwrap_or(file);
+    // Waited for in the background, as some openers only return once the window is closed
+    let mut child = Command::new("xdg-open")
+        .arg(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+// The tab data tags installs with I, flatpak installs with FI and full system installs with SI
+fn is_install(node: &ListNode) -> bool {
+    node.task_list.split_whitespace().any(|task| matches!(task, "I" | "FI" | "SI"))
+}
+
+// Bytes available to unprivileged users on the filesystem holding path. None when statvfs fails
+fn free_disk_space(path: &Path) -> Option<u64> {
+    use std::os::unix::ffi::OsStrExt;
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+// Initials of the words of a tab name for the sidebar rail, e.g. "SS" for "System Setup"
+fn tab_abbreviation(name: &str) -> String {
+    let initials: String = name
+        .split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .take(2)
+        .collect();
+    initials.to_uppercase()
+}
+
+// "Exit code: N", or why there is none, plus the number of attempts of retried commands
+fn exit_status_label(result: &CommandResult) -> String {
+    let label = match result.exit_code {
+        Some(code) => format!("Exit code: {}", code),
+        // Results of processes that never started carry no run time
+        None if result.elapsed.is_zero() => "(not started)".to_string(),
+        None => "(terminated by signal)".to_string(),
+    };
+    match result.failed_attempts.len() {
+        0 => label,
+        failed => format!("{} after {} attempts", label, failed + 1),
+    }
+}
+
+// Says that the start of the output is missing, and where to find all of it
+fn truncation_notice(result: &CommandResult) -> Option<String> {
+    if result.dropped_lines == 0 {
+        return None;
+    }
+    let mut notice = format!("Output truncated, showing the last {} lines ({} earlier lines dropped).",
+                             result.lines.len(), result.dropped_lines);
+    match &result.full_output {
+        Some(path) => notice.push_str(&format!(" Full output: {}", path.display())),
+        None => notice.push_str(" Turn on saving the full output in the settings to keep all of it."),
+    }
+    Some(notice)
+}
+
+fn format_result(output: &mut OutputText, result: &CommandResult, timestamps: bool, streams: OutputStreams) {
+    let attempts = result.failed_attempts.len() + 1;
+    for (i, attempt) in result.failed_attempts.iter().enumerate() {
+        output.push(&format!("── Attempt {} of {} ({}) ──\n", i + 1, attempts, exit_status_label(attempt)));
+        format_result(output, attempt, timestamps, streams);
+        output.push("\n\n");
+    }
+    if attempts > 1 {
+        output.push(&format!("── Attempt {} of {} ({}) ──\n", attempts, attempts, exit_status_label(result)));
+    }
+
+    output.push(match &result.verification {
+        _ if result.success => "✅ Command executed successfully!\n\n",
+        Some(verification) if !verification.passed => "❌ Command ran, but its verification failed!\n\n",
+        _ => "❌ Command failed!\n\n",
+    });
+    output.push(&format!("📁 {}\n\n", result.working_dir.display()));
+
+    let invalid_lines = result.lines.iter().filter(|line| line.invalid_utf8).count();
+    if invalid_lines > 0 {
+        output.push_stderr(&format!("⚠ {} line(s) were not valid UTF-8 and were decoded with the fallback \
+                                     encoding from the settings; they may not show correctly\n\n", invalid_lines), false);
+    }
+
+    if let Some(notice) = truncation_notice(result) {
+        output.push_stderr(&format!("⚠ {}\n\n", notice), false);
+    }
+
+    if timestamps {
+        output.push(&format!("{} started\n", format_elapsed(Duration::ZERO)));
+    }
+    let push_lines = |output: &mut OutputText, stderr: Option<bool>| {
+        for line in result.lines.iter().filter(|line| stderr.is_none_or(|stderr| line.is_stderr == stderr)) {
+            let text = if timestamps {
+                format!("{} {}\n", format_elapsed(line.elapsed), line.text)
             } else {
-                "Script executed successfully".to_string()
+                format!("{}\n", line.text)
             };
-            
-            CommandResult {
-                success,
-                output: result_output,
-                error: if success { None } else { Some(stderr) },
+            if line.is_stderr {
+                output.push_stderr(&text, !result.success);
+            } else {
+                output.push(&text);
             }
-        },
-        Err(e) => {
-            CommandResult {
-                success: false,
-                output: format!("Failed to execute script: {}", e),
-                error: Some(e.to_string()),
+        }
+    };
+    match streams {
+        OutputStreams::Interleaved => push_lines(output, None),
+        OutputStreams::StdoutOnly => push_lines(output, Some(false)),
+        OutputStreams::StderrOnly => push_lines(output, Some(true)),
+        OutputStreams::BothLabeled => {
+            for (heading, stderr) in [("── stdout ──\n", false), ("── stderr ──\n", true)] {
+                if result.lines.iter().any(|line| line.is_stderr == stderr) {
+                    output.push(heading);
+                    push_lines(output, Some(stderr));
+                }
             }
         }
     }
-}
\ No newline at end of file
+    if timestamps {
+        output.push(&format!("{} finished\n", format_elapsed(result.elapsed)));
+    }
+
+    // Nothing was captured, e.g. the command could not be spawned at all
+    if result.lines.is_empty() {
+        output.push(&result.output);
+        if let Some(error) = result.error.as_ref().filter(|_| !result.success) {
+            output.push("\n\nError: ");
+            output.push_stderr(error, true);
+        }
+    }
+
+    for change in &result.file_changes {
+        output.push(&format!("\n── Changes to {} ──\n{}\n", change.path.display(), change.text.trim_end()));
+    }
+
+    if let Some(verification) = &result.verification {
+        let outcome = if verification.passed { "Verified" } else { "Verification failed" };
+        output.push(&format!("\n── {}: {} ──\n", outcome, verification.command));
+        if verification.passed {
+            output.push(&format!("{}\n", verification.output.trim_end()));
+        } else {
+            output.push_stderr(&format!("{}\n", verification.output.trim_end()), true);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: u64, cmd: &str) -> Job {
+        Job {
+            id,
+            path: cmd.to_string(),
+            node: Arc::new(exec::tests::node(LinutilCommand::Raw(cmd.to_string()))),
+            env: BTreeMap::new(),
+            cwd: None,
+        }
+    }
+
+    #[test]
+    fn the_confirmation_dialog_shows_the_configured_shell() {
+        let _globals = exec::tests::GLOBALS.read().unwrap_or_else(|e| e.into_inner());
+        let job = Job { env: BTreeMap::from([("GREETING".to_string(), "hi".to_string())]), ..job(0, "echo $GREETING from $0") };
+        let batch = ExecOptions { shell: Some("bash".to_string()), ..Default::default() };
+        let opts = job.exec_options(&batch);
+
+        let resolved = resolve_command(&job.node.command, &opts).unwrap();
+        assert!(resolved.line.contains("bash -c "), "{}", resolved.line);
+        // Running the line the dialog shows does what the worker does
+        let shown = run_node(&exec::tests::node(LinutilCommand::Raw(resolved.line)), &ExecOptions::default());
+        let run = run_node(&job.node, &opts);
+        assert_eq!(run.stdout, "hi from bash\n");
+        assert_eq!(shown.stdout, run.stdout);
+    }
+
+    #[test]
+    fn identical_commands_in_different_places_are_different_entries() {
+        let node = exec::tests::node(LinutilCommand::Raw("echo same".to_string()));
+        let mut tree = linutil_core::ego_tree::Tree::new(node.clone());
+        let first = tree.root_mut().append(node.clone()).id();
+        let second = tree.root_mut().append(node.clone()).id();
+        let entry = |id, path: &str| ListEntry {
+            node: Arc::new(node.clone()),
+            id,
+            tab: 0,
+            path: path.to_string(),
+            is_directory: false,
+        };
+
+        let (a, b) = (entry(first, "Tab › A › Same"), entry(second, "Tab › B › Same"));
+        assert!(a.is_same(&a.clone()));
+        assert!(!a.is_same(&b));
+        assert!(!a.is_same(&ListEntry { tab: 1, ..a.clone() }));
+    }
+
+    #[test]
+    fn execute_all_keeps_the_selection_order() {
+        let _globals = exec::tests::GLOBALS.read().unwrap_or_else(|e| e.into_inner());
+        let selection = ["echo c", "echo a", "echo b"];
+        let mut next_id = 7;
+        let (queue, items) = queue_jobs(selection.iter().map(|cmd| job(0, cmd)).collect(), &mut next_id);
+        assert_eq!(next_id, 10);
+        let queued: Vec<(u64, String)> = queue.lock().unwrap().iter().map(|job| (job.id, job.path.clone())).collect();
+        assert_eq!(queued, [(7, "echo c".to_string()), (8, "echo a".to_string()), (9, "echo b".to_string())]);
+        let panel: Vec<&str> = items.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(panel, selection);
+
+        // A serial batch starts them in that order
+        let (command_tx, command_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        let worker = spawn_worker(command_rx, event_tx);
+        command_tx.send(QueuedBatch {
+            queue,
+            max_parallel: 1,
+            stop_on_failure: false,
+            exec: ExecOptions::default(),
+            exec_log: None,
+            retry: RetryPolicy { attempts: 0, base_delay: Duration::ZERO },
+        }).unwrap();
+        drop(command_tx);
+        worker.join().unwrap();
+        let started: Vec<u64> = event_rx.try_iter()
+            .filter_map(|event| match event {
+                CommandEvent::Started { id } => Some(id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(started, [7, 8, 9]);
+    }
+
+    #[test]
+    fn schedules_for_the_next_time_the_local_clock_shows() {
+        let now = SystemTime::now();
+        for (hour, minute) in [(0, 0), (3, 0), (12, 30), (23, 59)] {
+            let due = next_clock_time(now, hour, minute);
+            assert!(due > now);
+            assert!(due <= now + Duration::from_secs(25 * 3600));
+            let tm = local_time(due);
+            assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec), (hour as i32, minute as i32, 0));
+        }
+    }
+
+    #[test]
+    fn reads_free_disk_space_without_spawning_df() {
+        assert!(free_disk_space(Path::new("/")).is_some_and(|free| free > 0));
+        assert_eq!(free_disk_space(Path::new("/no/such/dir")), None);
+    }
+
+    #[test]
+    fn closing_stops_the_worker() {
+        let _globals = exec::tests::GLOBALS.write().unwrap_or_else(|e| e.into_inner());
+        let (command_tx, command_rx) = mpsc::channel();
+        let (event_tx, event_rx) = mpsc::channel();
+        let worker = spawn_worker(command_rx, event_tx);
+        command_tx.send(QueuedBatch {
+            queue: Arc::new(Mutex::new(VecDeque::from([job(1, "sleep 30"), job(2, "echo never")]))),
+            max_parallel: 1,
+            stop_on_failure: false,
+            exec: ExecOptions::default(),
+            exec_log: None,
+            retry: RetryPolicy { attempts: 0, base_delay: Duration::ZERO },
+        }).unwrap();
+        assert!(matches!(event_rx.recv_timeout(Duration::from_secs(5)), Ok(CommandEvent::Started { id: 1 })));
+        // Time for sh to start sleep, which is outside the process group here and outlives sh
+        thread::sleep(Duration::from_millis(300));
+
+        let closed_at = Instant::now();
+        let finished = stop_worker(Some(command_tx), Some(worker));
+        SHUTTING_DOWN.store(false, Ordering::Relaxed);
+        assert!(finished);
+        assert!(closed_at.elapsed() < WORKER_SHUTDOWN_WAIT);
+        // The running job was killed and the one after it never started
+        let events: Vec<CommandEvent> = event_rx.try_iter().collect();
+        assert!(matches!(events.as_slice(), [
+            CommandEvent::Finished { id: 1, result, .. },
+            CommandEvent::Skipped { id: 2, .. },
+        ] if !result.success));
+    }
+
+    #[test]
+    fn closing_does_not_wait_for_processes_that_left_the_group() {
+        let _globals = exec::tests::GLOBALS.write().unwrap_or_else(|e| e.into_inner());
+        // Stands in for a worker stuck reading pipes that a process outside the group holds open
+        let worker = thread::spawn(|| thread::sleep(Duration::from_secs(30)));
+
+        let closed_at = Instant::now();
+        let finished = stop_worker(None, Some(worker));
+        SHUTTING_DOWN.store(false, Ordering::Relaxed);
+        assert!(!finished);
+        assert!(closed_at.elapsed() < WORKER_SHUTDOWN_WAIT + Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::ModernTheme;
+use eframe::egui::{
+    text::{LayoutJob, TextFormat},
+    Color32, FontId,
+};
+
+// The bit of Markdown that command descriptions use: **bold**, `code`, "- " bullet lists and line
+// breaks. Anything else, including a marker that is never closed, stays as it was written.
+
+/// Lays out a description. `color` is the color of the plain text
+pub fn render(text: &str, theme: &ModernTheme, size: f32, color: Color32) -> LayoutJob {
+    let plain = TextFormat::simple(FontId::proportional(size), color);
+    let mut job = LayoutJob::default();
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            job.append("\n", 0.0, plain.clone());
+        }
+        let trimmed = line.trim_start();
+        let line = match trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+        {
+            Some(item) => {
+                job.append("  • ", 0.0, plain.clone());
+                item
+            }
+            None => line,
+        };
+        append_inline(&mut job, line, theme, size, &plain);
+    }
+    job
+}
+
+fn append_inline(
+    job: &mut LayoutJob,
+    mut line: &str,
+    theme: &ModernTheme,
+    size: f32,
+    plain: &TextFormat,
+) {
+    while !line.is_empty() {
+        let Some(start) = line.find(['`', '*']) else {
+            job.append(line, 0.0, plain.clone());
+            return;
+        };
+        let rest = &line[start..];
+        let span = if let Some(inner) = rest.strip_prefix("**") {
+            inner.find("**").filter(|&end| end > 0).map(|end| {
+                let format = TextFormat::simple(FontId::proportional(size), theme.on_surface);
+                (&inner[..end], format, end + 4)
+            })
+        } else if let Some(inner) = rest.strip_prefix('`') {
+            inner.find('`').filter(|&end| end > 0).map(|end| {
+                let format = TextFormat {
+                    background: theme.surface_variant,
+                    ..TextFormat::simple(FontId::monospace(size), theme.warning)
+                };
+                (&inner[..end], format, end + 2)
+            })
+        } else {
+            None
+        };
+
+        match span {
+            Some((content, format, len)) => {
+                job.append(&line[..start], 0.0, plain.clone());
+                job.append(content, 0.0, format);
+                line = &rest[len..];
+            }
+            // Not markup after all, so the marker is text
+            None => {
+                let marker = if rest.starts_with("**") { 2 } else { 1 };
+                job.append(&line[..start + marker], 0.0, plain.clone());
+                line = &rest[marker..];
+            }
+        }
+    }
+}
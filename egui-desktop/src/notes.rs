@@ -0,0 +1,32 @@
+use crate::config::data_dir;
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+// The user's own notes on commands, keyed by command path ("Tab › Directory › Command"). Kept in
+// the data directory rather than the config, as they are content the user wrote, not settings.
+
+fn notes_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("notes.json"))
+}
+
+pub fn load() -> BTreeMap<String, String> {
+    let Some(path) = notes_path() else {
+        return BTreeMap::new();
+    };
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse notes file {}: {}", path.display(), e);
+            BTreeMap::new()
+        }),
+        Err(_) => BTreeMap::new(),
+    }
+}
+
+pub fn save(notes: &BTreeMap<String, String>) -> io::Result<()> {
+    let path = notes_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory found"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(notes).map_err(io::Error::other)?;
+    fs::write(path, content)
+}
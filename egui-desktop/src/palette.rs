@@ -0,0 +1,95 @@
+// The Ctrl+P command palette: a flat, fuzzy searchable list of every command across all tabs. The
+// Ctrl+T tab switcher is the same list over tab names.
+
+/// How many matches the palette lists at most
+pub const MAX_MATCHES: usize = 50;
+
+pub struct Palette {
+    pub query: String,
+    /// Index into `matches` of the highlighted entry
+    pub selected: usize,
+    /// Breadcrumbs of all commands, e.g. "System Setup › Arch Linux › Paru AUR Helper"
+    paths: Vec<String>,
+    /// Indices into `paths`, best match first
+    matches: Vec<usize>,
+}
+
+impl Palette {
+    pub fn new(paths: Vec<String>) -> Self {
+        let mut palette = Self {
+            query: String::new(),
+            selected: 0,
+            paths,
+            matches: Vec::new(),
+        };
+        palette.update_matches();
+        palette
+    }
+
+    pub fn update_matches(&mut self) {
+        let mut scored: Vec<(i32, usize)> = self
+            .paths
+            .iter()
+            .enumerate()
+            .filter_map(|(i, path)| fuzzy_score(&self.query, path).map(|score| (score, i)))
+            .collect();
+        // Stable, so equal scores keep the sidebar order
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        self.matches = scored
+            .into_iter()
+            .take(MAX_MATCHES)
+            .map(|(_, i)| i)
+            .collect();
+        self.selected = 0;
+    }
+
+    pub fn matches(&self) -> impl Iterator<Item = &str> {
+        self.matches.iter().map(|&i| self.paths[i].as_str())
+    }
+
+    pub fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn selected_path(&self) -> Option<&str> {
+        self.matches
+            .get(self.selected)
+            .map(|&i| self.paths[i].as_str())
+    }
+}
+
+/// Scores how well `text` matches `query` when the query's characters appear in it in order, not
+/// necessarily next to each other. Consecutive characters and characters at the start of a word
+/// score higher. None when they don't all appear.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let mut score = 0;
+    let mut previous_match: Option<usize> = None;
+    let mut chars = text.chars().enumerate();
+    let mut previous_char = ' ';
+
+    for wanted in query.chars().filter(|c| !c.is_whitespace()) {
+        let wanted = wanted.to_ascii_lowercase();
+        loop {
+            let (i, c) = chars.next()?;
+            let word_start = !previous_char.is_alphanumeric();
+            previous_char = c;
+            if c.to_ascii_lowercase() != wanted {
+                continue;
+            }
+
+            score += 1;
+            if word_start {
+                score += 8;
+            }
+            match previous_match {
+                Some(previous) if previous + 1 == i => score += 5,
+                // Gaps cost a little, so tighter matches win
+                Some(previous) => score -= ((i - previous) as i32).min(5),
+                None => {}
+            }
+            previous_match = Some(i);
+            break;
+        }
+    }
+    Some(score)
+}
@@ -0,0 +1,129 @@
+use crate::{backends, exec::ExecOptions, Job};
+use linutil_core::ListNode;
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime},
+};
+
+// Jobs of a batch that the worker has not started yet. The UI keeps a handle to it, so pending
+// jobs can still be removed or reordered while the batch runs.
+pub type JobQueue = Arc<Mutex<VecDeque<Job>>>;
+
+/// A batch as handed to the worker
+pub struct QueuedBatch {
+    pub queue: JobQueue,
+    pub max_parallel: usize,
+    /// Skip the remaining jobs once one of them fails
+    pub stop_on_failure: bool,
+    /// Options every job runs with. The environment and working directory come from the job
+    pub exec: ExecOptions,
+    /// Audit log every finished job is appended to
+    pub exec_log: Option<PathBuf>,
+    pub retry: RetryPolicy,
+}
+
+/// How often a failing command that talks to the network is run again
+#[derive(Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 turns retrying off
+    pub attempts: u32,
+    /// Wait before the first retry, doubled for each one after it
+    pub base_delay: Duration,
+}
+
+/// Whether a failure of the command is likely a flaky mirror or connection rather than a real
+/// error, judged by it downloading something or using a package manager
+pub fn is_retryable(node: &ListNode) -> bool {
+    let Some(body) = backends::command_body(node) else {
+        return false;
+    };
+    body.split(|c: char| !c.is_ascii_alphabetic()).any(|word| {
+        matches!(word, "curl" | "wget")
+            || backends::PackageManager::ALL
+                .iter()
+                .any(|manager| manager.binary() == word)
+    })
+}
+
+/// A job waiting for its scheduled time. Schedules live in the app only, closing it drops them
+pub struct ScheduledJob {
+    pub job: Job,
+    pub due: SystemTime,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum QueueStatus {
+    Pending,
+    Running,
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+impl QueueStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            QueueStatus::Pending => "⏳ Pending",
+            QueueStatus::Running => "▶ Running",
+            QueueStatus::Succeeded => "✔ Succeeded",
+            QueueStatus::Failed => "✘ Failed",
+            QueueStatus::Skipped => "⏭ Skipped",
+        }
+    }
+
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            QueueStatus::Succeeded | QueueStatus::Failed | QueueStatus::Skipped
+        )
+    }
+}
+
+/// A job as shown in the queue panel
+pub struct QueueItem {
+    pub id: u64,
+    pub path: String,
+    pub status: QueueStatus,
+    queue: JobQueue,
+}
+
+impl QueueItem {
+    pub fn new(id: u64, path: String, queue: JobQueue) -> Self {
+        Self {
+            id,
+            path,
+            status: QueueStatus::Pending,
+            queue,
+        }
+    }
+
+    /// Takes the job out of its batch. Fails when the worker has already picked it up
+    pub fn remove_pending(&self) -> bool {
+        let mut queue = self.queue.lock().unwrap();
+        match queue.iter().position(|job| job.id == self.id) {
+            Some(index) => queue.remove(index).is_some(),
+            None => false,
+        }
+    }
+}
+
+/// Swaps two pending jobs of the same batch, both in the panel and in the order the worker runs
+/// them. Returns false when either has been started in the meantime.
+pub fn swap_pending(items: &mut [QueueItem], a: usize, b: usize) -> bool {
+    if !Arc::ptr_eq(&items[a].queue, &items[b].queue) {
+        return false;
+    }
+
+    {
+        let mut queue = items[a].queue.lock().unwrap();
+        let position = |id| queue.iter().position(|job: &Job| job.id == id);
+        let (Some(first), Some(second)) = (position(items[a].id), position(items[b].id)) else {
+            return false;
+        };
+        queue.swap(first, second);
+    }
+    items.swap(a, b);
+    true
+}
@@ -0,0 +1,176 @@
+use crate::{backends, config::AppConfig, selftest};
+use std::{collections::BTreeMap, env, fs};
+
+// The diagnostic report for bug reports: version, system, self-test, settings and the last run,
+// as Markdown to paste into an issue. Values of environment variables whose names match one of
+// AppConfig::redact_patterns are replaced wherever they appear in it.
+
+const REDACTED: &str = "[redacted]";
+// Shorter values would blank out unrelated text, e.g. a variable set to "1"
+const MIN_SECRET_LEN: usize = 4;
+
+/// The last finished run, as the output window shows it
+pub struct LastRun<'a> {
+    pub timing: &'a str,
+    pub output: &'a str,
+}
+
+pub fn build(config: &AppConfig, last_run: Option<LastRun>) -> String {
+    let redactor = Redactor::new(config);
+    let mut report = format!(
+        "# Linutil diagnostic report\n\nVersion: linutil-egui {}\n\n",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    report.push_str("## System\n\n");
+    for (name, value) in system_info() {
+        report.push_str(&format!("- {}: {}\n", name, value));
+    }
+
+    report.push_str("\n## Self-test\n\n");
+    for check in selftest::run(config) {
+        report.push_str(&format!(
+            "- {} {}: {}\n",
+            check.icon(),
+            check.name,
+            check.detail
+        ));
+    }
+
+    report.push_str("\n## Settings\n\n```json\n");
+    let settings = serde_json::to_string_pretty(&redactor.config(config))
+        .unwrap_or_else(|e| format!("Failed to serialize the settings: {}", e));
+    report.push_str(&settings);
+    report.push_str("\n```\n\n## Last run\n\n");
+    match last_run {
+        Some(LastRun { timing, output }) => {
+            report.push_str(&format!("{}\n\n```\n{}\n```\n", timing, output.trim_end()))
+        }
+        None => report.push_str("No command has run since the app started\n"),
+    }
+
+    redactor.text(&report)
+}
+
+fn system_info() -> Vec<(&'static str, String)> {
+    let os = fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|release| {
+            release.lines().find_map(|line| {
+                line.strip_prefix("PRETTY_NAME=")
+                    .map(|name| name.trim_matches('"').to_string())
+            })
+        })
+        .unwrap_or_else(|| "Unknown".to_string());
+    let kernel = fs::read_to_string("/proc/sys/kernel/osrelease")
+        .map(|release| release.trim().to_string())
+        .unwrap_or_else(|_| "Unknown".to_string());
+    let var = |name: &str| env::var(name).unwrap_or_else(|_| "(not set)".to_string());
+    let managers: Vec<&str> = backends::probe()
+        .into_iter()
+        .map(|manager| manager.binary())
+        .collect();
+
+    vec![
+        ("OS", os),
+        ("Kernel", kernel),
+        ("Architecture", env::consts::ARCH.to_string()),
+        ("Desktop", var("XDG_CURRENT_DESKTOP")),
+        ("Session", var("XDG_SESSION_TYPE")),
+        ("Shell", var("SHELL")),
+        ("Locale", var("LANG")),
+        ("Package managers", managers.join(", ")),
+    ]
+}
+
+struct Redactor {
+    /// Lowercase, matched anywhere in a variable name
+    patterns: Vec<String>,
+    /// Values of the matching variables, of the app's own environment and the configured ones
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    fn new(config: &AppConfig) -> Self {
+        let patterns: Vec<String> = config
+            .redact_patterns
+            .iter()
+            .map(|pattern| pattern.trim().to_lowercase())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+        let mut redactor = Self {
+            patterns,
+            secrets: Vec::new(),
+        };
+
+        let configured = config
+            .env
+            .iter()
+            .chain(config.env_profiles.values().flatten())
+            .chain(config.command_env.values().flatten())
+            .map(|(name, value)| (name.clone(), value.clone()));
+        // vars() would panic on a variable that isn't valid Unicode
+        let inherited = env::vars_os().filter_map(|(name, value)| {
+            Some((name.into_string().ok()?, value.into_string().ok()?))
+        });
+        let mut secrets: Vec<String> = inherited
+            .chain(configured)
+            .filter(|(name, value)| redactor.is_secret(name) && value.len() >= MIN_SECRET_LEN)
+            .map(|(_, value)| value)
+            .collect();
+        // Longest first, so a secret containing another one is replaced as a whole
+        secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+        secrets.dedup();
+        redactor.secrets = secrets;
+        redactor
+    }
+
+    fn is_secret(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| name.contains(pattern.as_str()))
+    }
+
+    /// The settings with the values of secret variables replaced
+    fn config(&self, config: &AppConfig) -> AppConfig {
+        let mut config = config.clone();
+        let redact = |vars: &mut BTreeMap<String, String>| {
+            for (name, value) in vars.iter_mut() {
+                if self.is_secret(name) {
+                    *value = REDACTED.to_string();
+                }
+            }
+        };
+        redact(&mut config.env);
+        config.env_profiles.values_mut().for_each(redact);
+        config.command_env.values_mut().for_each(redact);
+        config
+    }
+
+    /// Replaces the known secrets, and the value of every `NAME=value` with a secret name, e.g.
+    /// from a command that printed its environment
+    fn text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for secret in &self.secrets {
+            text = text.replace(secret.as_str(), REDACTED);
+        }
+        text.split_inclusive('\n')
+            .map(|line| self.assignment(line).unwrap_or_else(|| line.to_string()))
+            .collect()
+    }
+
+    fn assignment(&self, line: &str) -> Option<String> {
+        let (name, value) = line.split_once('=')?;
+        let variable = name.trim_start().trim_start_matches("export ").trim();
+        let is_name = !variable.is_empty()
+            && variable
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_name || !self.is_secret(variable) || value.trim().is_empty() {
+            return None;
+        }
+        let ending = if line.ends_with('\n') { "\n" } else { "" };
+        Some(format!("{}={}{}", name, REDACTED, ending))
+    }
+}
@@ -0,0 +1,149 @@
+use crate::{
+    backends::{self, find_on_path},
+    config::{data_dir, AppConfig},
+};
+use linutil_core::{try_get_tabs, Command};
+use serde::Serialize;
+use std::{fs, process};
+
+// Checks of whether this system can run linutil's commands at all, for --self-test and the
+// "Run self-test" button in the settings. Each one names what is wrong rather than just failing.
+
+#[derive(Serialize)]
+pub struct Check {
+    pub name: &'static str,
+    pub passed: bool,
+    /// Without it no command can run; the others only cost a feature
+    pub critical: bool,
+    pub detail: String,
+}
+
+impl Check {
+    /// "✔", "✘" for critical failures and "⚠" for the others
+    pub fn icon(&self) -> &'static str {
+        match (self.passed, self.critical) {
+            (true, _) => "✔",
+            (false, true) => "✘",
+            (false, false) => "⚠",
+        }
+    }
+}
+
+pub fn run(config: &AppConfig) -> Vec<Check> {
+    vec![
+        shell(config),
+        tab_data(config),
+        config_file(),
+        data_directory(),
+        package_managers(),
+        tool("xdg-open", "Showing scripts in the file manager"),
+    ]
+}
+
+/// Whether any critical check failed
+pub fn failed(checks: &[Check]) -> bool {
+    checks.iter().any(|check| check.critical && !check.passed)
+}
+
+// The shell inline commands run with, sh unless another one is configured
+fn shell(config: &AppConfig) -> Check {
+    let shell = config
+        .exec_options()
+        .shell
+        .unwrap_or_else(|| "sh".to_string());
+    let detail = match find_on_path(&shell) {
+        None => Err(format!("{} not found on PATH", shell)),
+        // Found is not enough: it has to start, too
+        Some(path) => match process::Command::new(&path).args(["-c", "exit 0"]).status() {
+            Ok(status) if status.success() => Ok(path.display().to_string()),
+            Ok(status) => Err(format!("{} does not run: {}", path.display(), status)),
+            Err(e) => Err(format!("{} does not run: {}", path.display(), e)),
+        },
+    };
+    check("Shell", true, detail)
+}
+
+fn tab_data(config: &AppConfig) -> Check {
+    let detail = try_get_tabs(config.validate).map(|tabs| {
+        let commands = tabs
+            .iter()
+            .flat_map(|tab| tab.tree.root().descendants())
+            .filter(|node| !matches!(node.value().command, Command::None))
+            .count();
+        format!("{} tabs with {} commands", tabs.len(), commands)
+    });
+    check("Tab data", true, detail)
+}
+
+fn config_file() -> Check {
+    let detail = match AppConfig::path() {
+        None => Err("No config directory found, settings can't be saved".to_string()),
+        Some(path) => match fs::read_to_string(&path) {
+            Err(_) if !path.exists() => Ok(format!(
+                "{} not created yet, defaults are used",
+                path.display()
+            )),
+            Err(e) => Err(format!("{} is not readable: {}", path.display(), e)),
+            Ok(content) => match serde_json::from_str::<AppConfig>(&content) {
+                Ok(_) => Ok(path.display().to_string()),
+                Err(e) => Err(format!(
+                    "{} is invalid, defaults are used: {}",
+                    path.display(),
+                    e
+                )),
+            },
+        },
+    };
+    check("Config file", false, detail)
+}
+
+// History, notes and the command log live here
+fn data_directory() -> Check {
+    let detail = match data_dir() {
+        None => Err("No data directory found, history and notes are not kept".to_string()),
+        Some(dir) => {
+            let probe = dir.join(".self-test");
+            let written = fs::create_dir_all(&dir).and_then(|()| fs::write(&probe, b""));
+            let _ = fs::remove_file(&probe);
+            match written {
+                Ok(()) => Ok(dir.display().to_string()),
+                Err(e) => Err(format!("{} is not writable: {}", dir.display(), e)),
+            }
+        }
+    };
+    check("Data directory", false, detail)
+}
+
+fn package_managers() -> Check {
+    let found: Vec<&str> = backends::probe()
+        .into_iter()
+        .map(|manager| manager.binary())
+        .collect();
+    let detail = if found.is_empty() {
+        Err(
+            "None of apt, dnf, pacman, zypper, flatpak or snap found, installs will fail"
+                .to_string(),
+        )
+    } else {
+        Ok(found.join(", "))
+    };
+    check("Package managers", false, detail)
+}
+
+fn tool(binary: &'static str, needed_for: &str) -> Check {
+    let detail = match find_on_path(binary) {
+        Some(path) => Ok(path.display().to_string()),
+        None => Err(format!("Not found on PATH. Needed for: {}", needed_for)),
+    };
+    check(binary, false, detail)
+}
+
+fn check(name: &'static str, critical: bool, detail: Result<String, String>) -> Check {
+    let passed = detail.is_ok();
+    Check {
+        name,
+        passed,
+        critical,
+        detail: detail.unwrap_or_else(|e| e),
+    }
+}
@@ -0,0 +1,331 @@
+use crate::config::KeymapPreset;
+use eframe::egui::{Context, Key, KeyboardShortcut, ModifierNames, Modifiers};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    ShowHelp,
+    FocusSearch,
+    /// Opens the command palette
+    CommandPalette,
+    /// Clears the search while it is focused, otherwise closes the topmost window
+    Close,
+    SelectPrevious,
+    SelectNext,
+    Activate,
+    GoBack,
+    /// Adds the focused command to the multi-selection or removes it
+    ToggleSelection,
+    /// Selects every multi-selectable command that is currently shown
+    SelectAll,
+    /// Shows or hides the window listing the selection
+    ToggleSelectionPanel,
+    /// Runs everything in the multi-selection
+    ExecuteSelection,
+    /// Index into the visible sidebar tabs
+    SwitchTab(usize),
+    /// Opens the search over tab names
+    TabSwitcher,
+    Reload,
+}
+
+impl Action {
+    /// Name of the action in the keybindings of the config. The tab numbers can't be rebound
+    pub fn config_name(self) -> Option<&'static str> {
+        Some(match self {
+            Action::ShowHelp => "show_help",
+            Action::FocusSearch => "focus_search",
+            Action::CommandPalette => "command_palette",
+            Action::Close => "close",
+            Action::SelectPrevious => "select_previous",
+            Action::SelectNext => "select_next",
+            Action::Activate => "activate",
+            Action::GoBack => "go_back",
+            Action::ToggleSelection => "toggle_selection",
+            Action::SelectAll => "select_all",
+            Action::ToggleSelectionPanel => "toggle_selection_panel",
+            Action::ExecuteSelection => "execute_selection",
+            Action::TabSwitcher => "tab_switcher",
+            Action::Reload => "reload",
+            Action::SwitchTab(_) => return None,
+        })
+    }
+
+    // Shortcuts with several bindings, like the tab numbers, tell the bindings apart by index
+    fn for_binding(self, index: usize) -> Self {
+        match self {
+            Action::SwitchTab(_) => Action::SwitchTab(index),
+            action => action,
+        }
+    }
+}
+
+pub struct Shortcut {
+    pub category: &'static str,
+    pub description: &'static str,
+    pub bindings: &'static [KeyboardShortcut],
+    pub action: Action,
+}
+
+const fn key(key: Key) -> KeyboardShortcut {
+    KeyboardShortcut::new(Modifiers::NONE, key)
+}
+
+// Single source for both the input handling and the help overlay, so the two can't drift apart
+pub const SHORTCUTS: &[Shortcut] = &[
+    Shortcut {
+        category: "General",
+        description: "Show keyboard shortcuts",
+        bindings: &[key(Key::F1), key(Key::Questionmark)],
+        action: Action::ShowHelp,
+    },
+    Shortcut {
+        category: "General",
+        description: "Clear the search / close the open window",
+        bindings: &[key(Key::Escape)],
+        action: Action::Close,
+    },
+    Shortcut {
+        category: "General",
+        description: "Reload the tab data",
+        bindings: &[KeyboardShortcut::new(Modifiers::COMMAND, Key::R)],
+        action: Action::Reload,
+    },
+    Shortcut {
+        category: "Search",
+        description: "Focus the search box",
+        bindings: &[key(Key::Slash)],
+        action: Action::FocusSearch,
+    },
+    Shortcut {
+        category: "Search",
+        description: "Find a command in all tabs",
+        bindings: &[KeyboardShortcut::new(Modifiers::COMMAND, Key::P)],
+        action: Action::CommandPalette,
+    },
+    Shortcut {
+        category: "Navigation",
+        description: "Select previous item",
+        bindings: &[key(Key::ArrowUp)],
+        action: Action::SelectPrevious,
+    },
+    Shortcut {
+        category: "Navigation",
+        description: "Select next item",
+        bindings: &[key(Key::ArrowDown)],
+        action: Action::SelectNext,
+    },
+    Shortcut {
+        category: "Navigation",
+        description: "Open directory / execute command",
+        bindings: &[key(Key::Enter)],
+        action: Action::Activate,
+    },
+    Shortcut {
+        category: "Navigation",
+        description: "Go back",
+        bindings: &[key(Key::Backspace)],
+        action: Action::GoBack,
+    },
+    Shortcut {
+        category: "Selection",
+        description: "Add / remove the focused command",
+        bindings: &[key(Key::Space)],
+        action: Action::ToggleSelection,
+    },
+    Shortcut {
+        category: "Selection",
+        description: "Select all commands shown",
+        bindings: &[KeyboardShortcut::new(Modifiers::COMMAND, Key::A)],
+        action: Action::SelectAll,
+    },
+    Shortcut {
+        category: "Selection",
+        description: "Show / hide the selection",
+        bindings: &[KeyboardShortcut::new(Modifiers::COMMAND, Key::L)],
+        action: Action::ToggleSelectionPanel,
+    },
+    Shortcut {
+        category: "Selection",
+        description: "Execute all selected commands",
+        bindings: &[KeyboardShortcut::new(Modifiers::COMMAND, Key::Enter)],
+        action: Action::ExecuteSelection,
+    },
+    Shortcut {
+        category: "Tabs",
+        description: "Switch to a tab by name",
+        bindings: &[KeyboardShortcut::new(Modifiers::COMMAND, Key::T)],
+        action: Action::TabSwitcher,
+    },
+    Shortcut {
+        category: "Tabs",
+        description: "Switch to tab 1-9",
+        bindings: &[
+            key(Key::Num1),
+            key(Key::Num2),
+            key(Key::Num3),
+            key(Key::Num4),
+            key(Key::Num5),
+            key(Key::Num6),
+            key(Key::Num7),
+            key(Key::Num8),
+            key(Key::Num9),
+        ],
+        action: Action::SwitchTab(0),
+    },
+];
+
+// Plain keys would steal input from text fields, so only keys that can't be typed stay active.
+// Ctrl+A keeps selecting the text of the field
+fn usable_while_typing(binding: &KeyboardShortcut) -> bool {
+    (!binding.modifiers.is_none() && binding.logical_key != Key::A)
+        || matches!(binding.logical_key, Key::F1 | Key::Escape)
+}
+
+// Added to the default keys by the vim preset. Plain letters are ignored while typing, so these
+// don't get in the way of the search box
+fn vim_binding(action: Action) -> Option<KeyboardShortcut> {
+    match action {
+        Action::SelectPrevious => Some(key(Key::K)),
+        Action::SelectNext => Some(key(Key::J)),
+        Action::Activate => Some(key(Key::L)),
+        Action::GoBack => Some(key(Key::H)),
+        _ => None,
+    }
+}
+
+/// The bindings in effect: SHORTCUTS with the preset and the overrides of the config applied
+pub struct Keymap {
+    /// Bindings of each entry of SHORTCUTS, in the same order
+    bindings: Vec<Vec<KeyboardShortcut>>,
+    /// Problems with the configured keybindings. Unknown actions and keys are ignored, and of
+    /// two actions sharing a key the one listed first in SHORTCUTS gets it
+    pub warnings: Vec<String>,
+}
+
+impl Keymap {
+    pub fn new(preset: KeymapPreset, overrides: &BTreeMap<String, Vec<String>>) -> Self {
+        let mut bindings: Vec<Vec<KeyboardShortcut>> = SHORTCUTS
+            .iter()
+            .map(|shortcut| {
+                let mut bindings = shortcut.bindings.to_vec();
+                if preset == KeymapPreset::Vim {
+                    bindings.extend(vim_binding(shortcut.action));
+                }
+                bindings
+            })
+            .collect();
+        let mut warnings = Vec::new();
+
+        for (name, keys) in overrides {
+            let Some(index) = SHORTCUTS
+                .iter()
+                .position(|shortcut| shortcut.action.config_name() == Some(name.as_str()))
+            else {
+                warnings.push(format!("Unknown action \"{}\"", name));
+                continue;
+            };
+            bindings[index] = keys
+                .iter()
+                .filter_map(|text| {
+                    let binding = parse_binding(text);
+                    if binding.is_none() {
+                        warnings.push(format!("\"{}\" of {} is not a key", text, name));
+                    }
+                    binding
+                })
+                .collect();
+        }
+
+        for (i, first) in bindings.iter().enumerate() {
+            for (j, second) in bindings.iter().enumerate().skip(i + 1) {
+                for binding in first.iter().filter(|binding| second.contains(binding)) {
+                    warnings.push(format!(
+                        "{} is bound to both \"{}\" and \"{}\", only the first one gets it",
+                        binding.format(&ModifierNames::NAMES, false),
+                        SHORTCUTS[i].description,
+                        SHORTCUTS[j].description
+                    ));
+                }
+            }
+        }
+
+        Self { bindings, warnings }
+    }
+
+    /// Consumes the shortcuts pressed this frame and returns their actions. Keys of actions that
+    /// `wanted` turns down are left for whatever else handles them
+    pub fn pressed(&self, ctx: &Context, wanted: impl Fn(Action) -> bool) -> Vec<Action> {
+        let typing = ctx.wants_keyboard_input();
+        let mut actions = Vec::new();
+        ctx.input_mut(|input| {
+            for (shortcut, bindings) in SHORTCUTS.iter().zip(&self.bindings) {
+                for (i, binding) in bindings.iter().enumerate() {
+                    let action = shortcut.action.for_binding(i);
+                    if wanted(action)
+                        && (!typing || usable_while_typing(binding))
+                        && input.consume_shortcut(binding)
+                    {
+                        actions.push(action);
+                    }
+                }
+            }
+        });
+        actions
+    }
+
+    /// Every shortcut with the keys it is bound to, in the order of SHORTCUTS
+    pub fn shortcuts(&self) -> impl Iterator<Item = (&'static Shortcut, &[KeyboardShortcut])> {
+        SHORTCUTS
+            .iter()
+            .zip(self.bindings.iter().map(Vec::as_slice))
+    }
+
+    /// The first binding of an action, for button tooltips
+    pub fn hint(&self, ctx: &Context, action: Action) -> String {
+        self.shortcuts()
+            .find(|(shortcut, _)| shortcut.action == action)
+            .and_then(|(_, bindings)| bindings.first())
+            .map(|binding| ctx.format_shortcut(binding))
+            .unwrap_or_default()
+    }
+}
+
+/// Human readable list of the bindings of a shortcut
+pub fn format_bindings(ctx: &Context, bindings: &[KeyboardShortcut]) -> String {
+    match bindings {
+        [] => "—".to_string(),
+        [first, .., last] if bindings.len() > 2 => {
+            format!(
+                "{} – {}",
+                ctx.format_shortcut(first),
+                ctx.format_shortcut(last)
+            )
+        }
+        bindings => bindings
+            .iter()
+            .map(|binding| ctx.format_shortcut(binding))
+            .collect::<Vec<_>>()
+            .join(" / "),
+    }
+}
+
+// "Ctrl+Shift+J", "ArrowDown", "F5", "/"
+fn parse_binding(text: &str) -> Option<KeyboardShortcut> {
+    // The key itself may be "+", so it is split off first
+    let (prefix, name) = match text.strip_suffix("++") {
+        Some(prefix) => (prefix, "+"),
+        None => text.rsplit_once('+').unwrap_or(("", text)),
+    };
+    let mut modifiers = Modifiers::NONE;
+    for modifier in prefix.split('+').filter(|modifier| !modifier.is_empty()) {
+        modifiers = modifiers
+            | match modifier.trim().to_ascii_lowercase().as_str() {
+                "ctrl" | "cmd" | "command" => Modifiers::COMMAND,
+                "alt" => Modifiers::ALT,
+                "shift" => Modifiers::SHIFT,
+                _ => return None,
+            };
+    }
+    Key::from_name(name.trim()).map(|key| KeyboardShortcut::new(modifiers, key))
+}
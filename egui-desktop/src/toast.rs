@@ -0,0 +1,169 @@
+use crate::ModernTheme;
+use eframe::egui::{self, Color32};
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// Short-lived notices stacked in the bottom right corner, for events that don't need a window:
+// a run started or finished, something was copied. They fade out on their own; clicking one
+// dismisses it.
+
+/// More than this and the oldest go, so a burst of events can't cover the window
+const MAX_TOASTS: usize = 5;
+/// The last part of a toast's time, over which it fades out
+const FADE: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn color(self, theme: &ModernTheme) -> Color32 {
+        match self {
+            Severity::Info => theme.primary,
+            Severity::Success => theme.success,
+            Severity::Warning => theme.warning,
+            Severity::Error => theme.danger,
+        }
+    }
+
+    fn icon(self) -> &'static str {
+        match self {
+            Severity::Info => "ℹ",
+            Severity::Success => "✔",
+            Severity::Warning => "⚠",
+            Severity::Error => "✘",
+        }
+    }
+
+    // Failures stay longer, as they are what needs reading
+    fn duration(self) -> Duration {
+        match self {
+            Severity::Info | Severity::Success => Duration::from_secs(4),
+            Severity::Warning => Duration::from_secs(6),
+            Severity::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+struct Toast {
+    severity: Severity,
+    text: String,
+    shown_at: Instant,
+    /// Clicking it opens the output window
+    opens_output: bool,
+}
+
+#[derive(Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, severity: Severity, text: String) {
+        self.add(severity, text, false);
+    }
+
+    /// A toast about a finished run, which opens its output when clicked
+    pub fn push_run(&mut self, severity: Severity, text: String) {
+        self.add(severity, text, true);
+    }
+
+    fn add(&mut self, severity: Severity, text: String, opens_output: bool) {
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.pop_front();
+        }
+        self.toasts.push_back(Toast {
+            severity,
+            text,
+            shown_at: Instant::now(),
+            opens_output,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.toasts.clear();
+    }
+
+    /// Draws the toasts, newest at the bottom. Returns whether one asking for the output window
+    /// was clicked
+    pub fn show(&mut self, ctx: &egui::Context, theme: &ModernTheme) -> bool {
+        self.toasts
+            .retain(|toast| toast.shown_at.elapsed() < toast.severity.duration());
+        let Some(next_change) = self
+            .toasts
+            .iter()
+            .map(|toast| {
+                let left = toast
+                    .severity
+                    .duration()
+                    .saturating_sub(toast.shown_at.elapsed());
+                // Fading needs every frame, until then nothing changes
+                left.checked_sub(FADE).unwrap_or(Duration::ZERO)
+            })
+            .min()
+        else {
+            return false;
+        };
+        ctx.request_repaint_after(next_change);
+
+        let mut dismissed = None;
+        let mut open_output = false;
+        // Above the status bar
+        egui::Area::new(egui::Id::new("toasts"))
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-16.0, -48.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                for (i, toast) in self.toasts.iter().enumerate() {
+                    let left = toast
+                        .severity
+                        .duration()
+                        .saturating_sub(toast.shown_at.elapsed());
+                    let opacity = (left.as_secs_f32() / FADE.as_secs_f32()).min(1.0);
+                    let color = toast.severity.color(theme);
+                    let response = ui
+                        .scope(|ui| {
+                            ui.multiply_opacity(opacity);
+                            egui::Frame::none()
+                                .fill(theme.surface_variant)
+                                .stroke(egui::Stroke::new(1.0, color))
+                                .rounding(8.0)
+                                .inner_margin(egui::Margin::symmetric(12.0, 8.0))
+                                .show(ui, |ui| {
+                                    ui.set_max_width(360.0);
+                                    ui.label(
+                                        egui::RichText::new(format!(
+                                            "{} {}",
+                                            toast.severity.icon(),
+                                            toast.text
+                                        ))
+                                        .color(color),
+                                    );
+                                })
+                                .response
+                        })
+                        .inner
+                        .interact(egui::Sense::click());
+                    let hint = if toast.opens_output {
+                        "Click to show the output"
+                    } else {
+                        "Click to dismiss"
+                    };
+                    if response.on_hover_text(hint).clicked() {
+                        dismissed = Some(i);
+                        open_output |= toast.opens_output;
+                    }
+                    ui.add_space(6.0);
+                }
+            });
+        if let Some(i) = dismissed {
+            self.toasts.remove(i);
+        }
+        open_output
+    }
+}